@@ -1,41 +1,232 @@
 use game;
 use graphics;
 use units;
-use units::{AsPixel};
+use units::{AsGame,AsPixel};
 
 use sdl2::rect::Rect;
 
+/// How far apart copies of a backdrop are painted, should its image
+/// not report its own size.
 static BACKGROUND_SIZE: units::Tile = units::Tile(4);
 
+/// Anything painted behind the map.
+///
+/// Backdrops are drawn in screen-space, they are given the position
+/// of the camera so they may decide for themselves how to scroll.
+pub trait Backdrop {
+	fn update(&mut self, _elapsed_time: units::Millis) {}
+	fn draw(&mut self, graphics: &mut graphics::Graphics, camera: (units::Game, units::Game));
+}
+
+/// Describes a single backdrop, as selected by a map.
+#[derive(Clone,PartialEq)]
+pub enum Layer {
+	/// Does not move w/ the camera.
+	Fixed(String),
+	/// Moves at a fraction of the camera's speed.
+	Parallax(String, f64),
+	/// Continuously drifts horizontally, regardless of the camera.
+	Scrolling(String, units::Velocity),
+}
+
+/// Loads the backdrops described by `layers`, the first of which is
+/// painted furthest back. Several layers are combined into a stack.
+pub fn from_layers(layers: &[Layer], graphics: &mut graphics::Graphics) -> Box<Backdrop> {
+	let mut backdrops: Vec<Box<Backdrop>> = layers.iter().map(|layer| {
+		match *layer {
+			Layer::Fixed(ref path) =>
+				Box::new(FixedBackdrop::new(path.clone(), graphics)) as Box<Backdrop>,
+			Layer::Parallax(ref path, speed) =>
+				Box::new(ParallaxBackdrop::new(path.clone(), speed, graphics)) as Box<Backdrop>,
+			Layer::Scrolling(ref path, velocity) =>
+				Box::new(ScrollingBackdrop::new(path.clone(), velocity, graphics)) as Box<Backdrop>,
+		}
+	}).collect();
+
+	if backdrops.len() == 1 {
+		backdrops.pop().unwrap()
+	} else {
+		Box::new(LayeredBackdrop::new(backdrops))
+	}
+}
+
 pub struct FixedBackdrop {
 	surface_id: String,
+	size:       (units::Game, units::Game),
 }
 
 impl FixedBackdrop {
 	pub fn new(path: String,
 	               graphics: &mut graphics::Graphics) -> FixedBackdrop {
 		graphics.load_image(path.clone(), false);
-		FixedBackdrop { surface_id: path }
+		FixedBackdrop { size: image_size(graphics, &path[..]), surface_id: path }
+	}
+}
+
+impl Backdrop for FixedBackdrop {
+	fn draw(&mut self, graphics: &mut graphics::Graphics, _camera: (units::Game, units::Game)) {
+		tile_screen(graphics, &self.surface_id[..], self.size, (units::Game(0.0), units::Game(0.0)));
 	}
+}
+
+/// Scrolls by `speed` times the distance the camera has moved.
+/// (e.g: `0.5` appears twice as far away as the map, `0.0` is fixed.)
+pub struct ParallaxBackdrop {
+	surface_id: String,
+	size:       (units::Game, units::Game),
+	speed:      f64,
+}
 
-	/// Repeatedly paints the asset across the entire screen.
-	/// Moving the destination rectangle `BACKGROUND_SIZE` pixels
-	/// in either direction as it progresses.
-	pub fn draw(&mut self, graphics: &mut graphics::Graphics) {
-		let (mut x, mut y) = (0i32,0i32);
-		let units::Pixel(tile_size) = BACKGROUND_SIZE.to_pixel();
+impl ParallaxBackdrop {
+	pub fn new(path: String, speed: f64,
+	           graphics: &mut graphics::Graphics) -> ParallaxBackdrop {
+		graphics.load_image(path.clone(), true);
+		ParallaxBackdrop { size: image_size(graphics, &path[..]), surface_id: path, speed: speed }
+	}
+}
 
-		while units::Pixel(x) < game::SCREEN_WIDTH.to_pixel() {
-			while units::Pixel(y) < game::SCREEN_HEIGHT.to_pixel() {
-				let src  = Rect::new(0, 0, tile_size as u32, tile_size as u32);
-				let dest = Rect::new(x, y, tile_size as u32, tile_size as u32);
+impl Backdrop for ParallaxBackdrop {
+	fn draw(&mut self, graphics: &mut graphics::Graphics, camera: (units::Game, units::Game)) {
+		let (camera_x, camera_y) = camera;
+		let scroll = (camera_x * units::Game(self.speed), camera_y * units::Game(self.speed));
+		tile_screen(graphics, &self.surface_id[..], self.size, scroll);
+	}
+}
 
-				graphics.blit_surface(&self.surface_id[..], &src, &dest);
-				y+= tile_size as i32;
-			}
+/// Drifts sideways at a constant `velocity`, like Cave Story's moving clouds.
+pub struct ScrollingBackdrop {
+	surface_id: String,
+	size:       (units::Game, units::Game),
+	velocity:   units::Velocity,
+	offset:     units::Game,
+}
 
-			x += tile_size as i32;
-			y = 0;
+impl ScrollingBackdrop {
+	pub fn new(path: String, velocity: units::Velocity,
+	           graphics: &mut graphics::Graphics) -> ScrollingBackdrop {
+		graphics.load_image(path.clone(), true);
+		ScrollingBackdrop {
+			size: image_size(graphics, &path[..]), surface_id: path,
+			velocity: velocity, offset: units::Game(0.0),
 		}
 	}
 }
+
+impl Backdrop for ScrollingBackdrop {
+	fn update(&mut self, elapsed_time: units::Millis) {
+		// keep the offset small, it only matters modulo the size of the image
+		let offset = self.offset + (self.velocity * elapsed_time);
+		self.offset = wrap(offset, self.size.0);
+	}
+
+	fn draw(&mut self, graphics: &mut graphics::Graphics, _camera: (units::Game, units::Game)) {
+		tile_screen(graphics, &self.surface_id[..], self.size, (self.offset, units::Game(0.0)));
+	}
+}
+
+/// Paints several backdrops on top of one another, back to front.
+pub struct LayeredBackdrop {
+	layers: Vec<Box<Backdrop>>,
+}
+
+impl LayeredBackdrop {
+	pub fn new(layers: Vec<Box<Backdrop>>) -> LayeredBackdrop {
+		LayeredBackdrop { layers: layers }
+	}
+}
+
+impl Backdrop for LayeredBackdrop {
+	fn update(&mut self, elapsed_time: units::Millis) {
+		for layer in self.layers.iter_mut() {
+			layer.update(elapsed_time);
+		}
+	}
+
+	fn draw(&mut self, graphics: &mut graphics::Graphics, camera: (units::Game, units::Game)) {
+		for layer in self.layers.iter_mut() {
+			layer.draw(graphics, camera);
+		}
+	}
+}
+
+/// Wraps `offset` into the range `[0, size)`
+fn wrap(offset: units::Game, size: units::Game) -> units::Game {
+	let (units::Game(offset), units::Game(size)) = (offset, size);
+	units::Game(((offset % size) + size) % size)
+}
+
+/// The size of the image loaded from `path`, which is how far apart
+/// copies of it are painted.
+fn image_size(graphics: &graphics::Graphics, path: &str) -> (units::Game, units::Game) {
+	match graphics.image_size(path) {
+		Some((w, h)) => (units::Pixel(w as i32).to_game(), units::Pixel(h as i32).to_game()),
+		None => (BACKGROUND_SIZE.to_game(), BACKGROUND_SIZE.to_game()),
+	}
+}
+
+/// Repeatedly paints the asset across the entire screen.
+/// Moving the destination rectangle by the image's `size`
+/// in either direction as it progresses.
+///
+/// The pattern is shifted left & up by `scroll`.
+fn tile_screen(graphics: &mut graphics::Graphics, surface_id: &str,
+               size: (units::Game, units::Game), scroll: (units::Game, units::Game)) {
+	let ((width, height), (scroll_x, scroll_y)) = (size, scroll);
+	let (units::Pixel(tile_w), units::Pixel(tile_h)) = (width.to_pixel(), height.to_pixel());
+	let (units::Pixel(start_x), units::Pixel(start_y)) =
+		(wrap(scroll_x, width).to_pixel(),
+		 wrap(scroll_y, height).to_pixel());
+
+	let (mut x, mut y) = (-start_x, -start_y);
+
+	while units::Pixel(x) < game::SCREEN_WIDTH.to_pixel() {
+		while units::Pixel(y) < game::SCREEN_HEIGHT.to_pixel() {
+			let src  = Rect::new(0, 0, tile_w as u32, tile_h as u32);
+			let dest = Rect::new(x, y, tile_w as u32, tile_h as u32);
+
+			graphics.blit_surface(surface_id, &src, &dest);
+			y+= tile_h;
+		}
+
+		x += tile_w;
+		y = -start_y;
+	}
+}
+
+#[test]
+fn test_wrap() {
+	let size = units::Game(128.0);
+	assert!(wrap(units::Game(130.0), size) == units::Game(2.0));
+	assert!(wrap(units::Game(-2.0), size)  == units::Game(126.0));
+}
+
+#[test]
+fn test_parallax_scrolls_slower_than_camera() {
+	let mut display = graphics::Graphics::headless();
+	let mut backdrop = from_layers(&[Layer::Parallax(format!("bk.bmp"), 0.25)], &mut display);
+
+	display.clear_buffer();
+	backdrop.draw(&mut display, (units::Game(40.0), units::Game(0.0)));
+
+	let first = display.headless_backend().unwrap().blits()[0].dest_rect;
+	assert_eq!((first.x(), first.y()), (-10, 0));
+}
+
+#[test]
+fn test_scrolling_wraps_at_image_width() {
+	let mut backend = graphics::HeadlessBackend::new();
+	backend.insert_image(format!("clouds.bmp"), 100, 60, vec![0xffffff; 100 * 60]);
+
+	let mut display = graphics::Graphics::from_backend(Box::new(backend));
+	let mut backdrop = from_layers(&[Layer::Scrolling(format!("clouds.bmp"), units::Velocity(0.1))],
+	                               &mut display);
+
+	// drifts 103 games: once across the image, then 3 more
+	backdrop.update(units::Millis(1030));
+	display.clear_buffer();
+	backdrop.draw(&mut display, (units::Game(0.0), units::Game(0.0)));
+
+	let blits = display.headless_backend().unwrap().blits();
+	assert_eq!((blits[0].dest_rect.width(), blits[0].dest_rect.height()), (100, 60));
+	assert_eq!((blits[0].dest_rect.x(), blits[1].dest_rect.y()), (-3, 60));
+}
@@ -0,0 +1,264 @@
+use collisions::Rectangle;
+use game;
+use map;
+use player;
+use sprite::{Facing, Looking};
+
+use units;
+use units::AsGame;
+
+// size of the region, centered on screen, the player may move freely within
+static DEAD_ZONE_W: units::Game = units::Game(64.0);
+static DEAD_ZONE_H: units::Game = units::Game(128.0);
+
+/// How far ahead of the player, in the direction they are facing
+/// or looking, the camera will aim.
+pub static LOOK_AHEAD: units::Game = units::Game(64.0);
+
+/// How quickly the camera pans towards its look-ahead point.
+static PAN_SPEED: units::Velocity = units::Velocity(0.2);
+
+/// Decides which portion of the map is visible on screen.
+///
+/// The camera's position is the top-left corner of the screen, expressed
+/// in map coordinates. It is never moved beyond the edges of the map.
+pub struct Camera {
+	x: units::Game,
+	y: units::Game,
+	prev_x: units::Game,
+	prev_y: units::Game,
+
+	dead_zone:   Rectangle,
+	look_ahead:  units::Game,
+
+	// current look-ahead, eased towards its target
+	look_x: units::Game,
+	look_y: units::Game,
+}
+
+/// The region of the screen (in screen-space) the player may move freely
+/// within before the camera starts following them.
+pub fn dead_zone() -> Rectangle {
+	Rectangle {
+		x: (game::SCREEN_WIDTH.to_game() - DEAD_ZONE_W) / units::Game(2.0),
+		y: (game::SCREEN_HEIGHT.to_game() - DEAD_ZONE_H) / units::Game(2.0),
+		width: DEAD_ZONE_W, height: DEAD_ZONE_H,
+	}
+}
+
+impl Camera {
+	/// A camera at the origin which keeps its target within `dead_zone`
+	/// and aims `look_ahead` units in front of it.
+	pub fn new(dead_zone: Rectangle, look_ahead: units::Game) -> Camera {
+		Camera {
+			x: units::Game(0.0), y: units::Game(0.0),
+			prev_x: units::Game(0.0), prev_y: units::Game(0.0),
+
+			dead_zone: dead_zone,
+			look_ahead: look_ahead,
+
+			look_x: units::Game(0.0),
+			look_y: units::Game(0.0),
+		}
+	}
+
+	/// The camera's position, `alpha` of the way between the previous tick
+	/// and the current one.
+	pub fn position(&self, alpha: f64) -> (units::Game, units::Game) {
+		(self.prev_x.lerp(self.x, alpha), self.prev_y.lerp(self.y, alpha))
+	}
+
+	/// Immediately centers the camera on `player`, w/o any easing.
+	pub fn center_on(&mut self, player: &player::Player, map: &map::Map) {
+		let (center_x, center_y) = player.center();
+		self.look_x = units::Game(0.0);
+		self.look_y = units::Game(0.0);
+
+		self.x = center_x - (game::SCREEN_WIDTH.to_game() / units::Game(2.0));
+		self.y = center_y - (game::SCREEN_HEIGHT.to_game() / units::Game(2.0));
+		self.clamp(map);
+
+		self.prev_x = self.x;
+		self.prev_y = self.y;
+	}
+
+	/// Follows `player`: the camera only moves once the point it is
+	/// aiming for leaves the dead zone.
+	pub fn update(&mut self, elapsed_time: units::Millis,
+	              player: &player::Player, map: &map::Map) {
+		self.prev_x = self.x;
+		self.prev_y = self.y;
+
+		// ease look-ahead towards where the player is facing / looking
+		let target_x = match player.facing() {
+			Facing::West => units::Game(0.0) - self.look_ahead,
+			Facing::East => self.look_ahead,
+		};
+
+		let target_y = match player.looking() {
+			Looking::Up         => units::Game(0.0) - self.look_ahead,
+			Looking::Down       => self.look_ahead,
+			Looking::Horizontal => units::Game(0.0),
+		};
+
+		let max_pan = PAN_SPEED * elapsed_time;
+		self.look_x = approach(self.look_x, target_x, max_pan);
+		self.look_y = approach(self.look_y, target_y, max_pan);
+
+		// keep the focus point within the dead zone
+		let (center_x, center_y) = player.center();
+		let (focus_x, focus_y)   = (center_x + self.look_x, center_y + self.look_y);
+
+		if focus_x - self.x < self.dead_zone.left() {
+			self.x = focus_x - self.dead_zone.left();
+		} else if focus_x - self.x > self.dead_zone.right() {
+			self.x = focus_x - self.dead_zone.right();
+		}
+
+		if focus_y - self.y < self.dead_zone.top() {
+			self.y = focus_y - self.dead_zone.top();
+		} else if focus_y - self.y > self.dead_zone.bottom() {
+			self.y = focus_y - self.dead_zone.bottom();
+		}
+
+		self.clamp(map);
+	}
+
+	/// Keeps the screen within the bounds of `map`.
+	/// Maps smaller than the screen are centered instead.
+	fn clamp(&mut self, map: &map::Map) {
+		self.x = clamp_axis(self.x, map.width(), game::SCREEN_WIDTH.to_game());
+		self.y = clamp_axis(self.y, map.height(), game::SCREEN_HEIGHT.to_game());
+	}
+}
+
+fn clamp_axis(position: units::Game, map_size: units::Game, screen_size: units::Game) -> units::Game {
+	if map_size <= screen_size {
+		return (map_size - screen_size) / units::Game(2.0);
+	}
+
+	let max = map_size - screen_size;
+	if position < units::Game(0.0) { units::Game(0.0) }
+	else if position > max { max }
+	else { position }
+}
+
+/// Moves `from` towards `to` by no more than `max_delta`
+fn approach(from: units::Game, to: units::Game, max_delta: units::Game) -> units::Game {
+	if from < to {
+		if to - from < max_delta { to } else { from + max_delta }
+	} else {
+		if from - to < max_delta { to } else { from - max_delta }
+	}
+}
+
+#[test]
+fn test_camera_clamps_to_map() {
+	use graphics;
+
+	let mut display = graphics::Graphics::headless();
+	let map = map::Map::create_test_map(&mut display);
+	let quote = player::Player::new(&mut display, units::Game(0.0), units::Game(0.0));
+
+	// the demo map is exactly one screen in size
+	let mut camera = Camera::new(dead_zone(), LOOK_AHEAD);
+	camera.center_on(&quote, &map);
+	for tick in 0..60 {
+		camera.update(game::timestep(tick), &quote, &map);
+	}
+
+	assert!(camera.position(1.0) == (units::Game(0.0), units::Game(0.0)));
+}
+
+#[test]
+fn test_clamp_axis() {
+	let (screen, map) = (units::Game(640.0), units::Game(1280.0));
+	assert!(clamp_axis(units::Game(-10.0), map, screen) == units::Game(0.0));
+	assert!(clamp_axis(units::Game(320.0), map, screen) == units::Game(320.0));
+	assert!(clamp_axis(units::Game(900.0), map, screen) == units::Game(640.0));
+	assert!(clamp_axis(units::Game(100.0), units::Game(320.0), screen) == units::Game(-160.0));
+}
+
+/// An empty map of 60 * 30 tiles: three screens across & two down.
+#[cfg(test)]
+fn create_open_map(display: &mut ::graphics::Graphics) -> map::Map {
+	let map_file = map::loader::parse("
+		backdrop  assets/base/bkBlue.bmp
+		tileset   assets/base/Stage/PrtCave.bmp
+		size      60 30
+	").unwrap();
+
+	map::Map::from_file(display, &map_file)
+}
+
+#[test]
+fn test_camera_holds_still_within_dead_zone() {
+	use graphics;
+
+	let mut display = graphics::Graphics::headless();
+	let map = create_open_map(&mut display);
+	let (x, y) = (units::Tile(30).to_game(), units::Tile(15).to_game());
+
+	// w/o any look-ahead the camera only follows the player's own movement
+	let mut camera = Camera::new(dead_zone(), units::Game(0.0));
+	camera.center_on(&player::Player::new(&mut display, x, y), &map);
+	let centered = camera.position(1.0);
+
+	let nudged = player::Player::new(&mut display, x + units::Game(16.0), y - units::Game(48.0));
+	camera.update(game::timestep(0), &nudged, &map);
+	assert!(camera.position(1.0) == centered);
+
+	// leaving the dead zone drags the camera along, keeping the player on its edge
+	let beyond = player::Player::new(&mut display, x + units::Game(100.0), y);
+	camera.update(game::timestep(1), &beyond, &map);
+	let (center_x, _) = beyond.center();
+	assert!(camera.position(1.0) == (center_x - dead_zone().right(), centered.1));
+}
+
+#[test]
+fn test_camera_looks_ahead_of_player() {
+	use graphics;
+
+	let mut display = graphics::Graphics::headless();
+	let map = create_open_map(&mut display);
+	let (x, y) = (units::Tile(30).to_game(), units::Tile(15).to_game());
+
+	for &facing in [Facing::East, Facing::West].iter() {
+		let mut quote = player::Player::new(&mut display, x, y);
+		if facing == Facing::West { quote.start_moving_left(); quote.stop_moving(); }
+
+		let mut camera = Camera::new(dead_zone(), LOOK_AHEAD);
+		camera.center_on(&quote, &map);
+		for tick in 0..60 {
+			camera.update(game::timestep(tick), &quote, &map);
+		}
+
+		// the point ahead of the player rests on the far edge of the dead zone
+		let (center_x, _) = quote.center();
+		let (camera_x, _) = camera.position(1.0);
+		match facing {
+			Facing::East => assert!(camera_x == center_x + LOOK_AHEAD - dead_zone().right()),
+			Facing::West => assert!(camera_x == center_x - LOOK_AHEAD - dead_zone().left()),
+		}
+	}
+}
+
+#[test]
+fn test_camera_stops_at_map_edge() {
+	use graphics;
+
+	let mut display = graphics::Graphics::headless();
+	let map = create_open_map(&mut display);
+	let mut camera = Camera::new(dead_zone(), LOOK_AHEAD);
+	camera.center_on(&player::Player::new(&mut display, units::Tile(30).to_game(), units::Tile(15).to_game()), &map);
+
+	// run to the bottom-right corner, the camera follows as far as the map allows
+	for tick in 0..120 {
+		let quote = player::Player::new(&mut display,
+			units::Tile(30 + tick as usize / 4).to_game(), units::Tile(15 + tick as usize / 8).to_game());
+		camera.update(game::timestep(tick), &quote, &map);
+	}
+
+	assert!(camera.position(1.0) == (map.width() - game::SCREEN_WIDTH.to_game(),
+	                                 map.height() - game::SCREEN_HEIGHT.to_game()));
+}
@@ -2,8 +2,11 @@ use std::collections::hash_map::{HashMap, Entry};
 use num::Float;
 
 use collisions::Rectangle;
+use enemies::{Enemy, Health};
 use sprite::{self, Facing};
 use graphics;
+use map;
+use player;
 
 use units;
 use units::AsGame;
@@ -22,14 +25,19 @@ static EAST_OFFSET: units::Tile = units::Tile(1);
 static SPRITE_FRAMES: units::Frame =  3;
 static SPRITE_FPS: units::Fps      = 15;
 
+static CONTACT_DAMAGE: units::HitPoints = 1;
+static MAX_HEALTH: units::HitPoints     = 3;
+
 pub struct CaveBat {
 	x: units::Game,
 	y: units::Game,
+	prev_y: units::Game,
 	origin: (units::Game, units::Game),
 
 	flight_angle: units::Degrees,
 
 	facing:   Facing,
+	health:   Health,
 	sprites:  HashMap<sprite::Facing, Box<sprite::Updatable<units::Game>>>,
 }
 
@@ -41,10 +49,12 @@ impl CaveBat {
 
 		let mut new_bat = CaveBat { 
 			x: x, y: y,
+			prev_y: y,
 			origin: (x,y),
 
 			facing:        Facing::West,
 			flight_angle:  units::Degrees(0.0), 
+			health:        Health::new(MAX_HEALTH),
 
 			sprites: sprite_map,
 		};
@@ -80,18 +90,14 @@ impl CaveBat {
 		};
 	}
 
-	pub fn damage_rectangle(&self) -> Rectangle {
-		Rectangle {
-			x: self.x + units::HalfTile(1), y: self.y + units::HalfTile(1),
-			width: units::Game(0.0), height: units::Game(0.0),
-		}
-	}
-	
 	fn center_x(&self) -> units::Game {
 		self.x + units::HalfTile(1)
 	}
+}
 
-	pub fn update(&mut self, elapsed_time: units::Millis, player_x: units::Game) {
+impl Enemy for CaveBat {
+	fn update(&mut self, elapsed_time: units::Millis,
+	          _map: &map::Map, player: &player::Player) {
 		let av: units::Degrees = ANGULAR_VELOCITY * elapsed_time;
 		let amp: units::Game = // peak height of the wave in game units
 			units::HalfTile(5).to_game();
@@ -103,15 +109,46 @@ impl CaveBat {
 			);
 
 		let (_,y0) = self.origin;
+		self.prev_y = self.y;
 		self.y = y0 + (amp * wave);
 
-		self.facing = if self.center_x() > player_x 
+		self.facing = if self.center_x() > player.center_x()
 			{ Facing::West } else { Facing::East };
 		
 		self.sprites.get_mut(&self.facing).unwrap().update(elapsed_time);
+		self.health.update(elapsed_time);
+	}
+
+	fn draw(&mut self, display: &mut graphics::Graphics, alpha: f64) {
+		if self.health.is_flashing() { return; }
+
+		let (x, y) = (self.x + self.health.shake(), self.prev_y.lerp(self.y, alpha));
+		self.sprites.get_mut(&self.facing).unwrap().draw(display, (x, y));
+	}
+
+	fn damage_rectangle(&self) -> Rectangle {
+		Rectangle {
+			x: self.x + units::HalfTile(1), y: self.y + units::HalfTile(1),
+			width: units::Game(0.0), height: units::Game(0.0),
+		}
+	}
+
+	fn collision_rectangle(&self) -> Rectangle {
+		Rectangle {
+			x: self.x, y: self.y,
+			width: units::Tile(1).to_game(), height: units::Tile(1).to_game(),
+		}
+	}
+
+	fn contact_damage(&self) -> units::HitPoints {
+		CONTACT_DAMAGE
+	}
+
+	fn take_damage(&mut self, damage: units::HitPoints) {
+		self.health.take_damage(damage);
 	}
 
-	pub fn draw(&mut self, display: &mut graphics::Graphics) {
-		self.sprites.get_mut(&self.facing).unwrap().draw(display, (self.x, self.y));
+	fn is_dead(&self) -> bool {
+		self.health.is_dead()
 	}
 }
@@ -1,5 +1,131 @@
+use std::cmp;
+
+use collisions::Rectangle;
+use graphics;
+use map;
+use player;
+use units;
+
 // Bring enemies into this crate's namespace
 pub use enemies::bat::CaveBat;
 
 // Load enemy modules
 pub mod bat;
+
+/// How long an enemy flashes & shakes after being hit.
+static HURT_TIME: units::Millis     = units::Millis(320);
+static FLASH_PERIOD: units::Millis  = units::Millis(40);
+static SHAKE_PERIOD: units::Millis  = units::Millis(32);
+static SHAKE_DISTANCE: units::Game  = units::Game(2.0);
+
+/// Anything in the world which means the player harm.
+pub trait Enemy {
+	/// Moves the enemy, which may react to the map & the player.
+	fn update(&mut self, elapsed_time: units::Millis,
+	          map: &map::Map, player: &player::Player);
+
+	/// Draws the enemy `alpha` of the way between its previous & current position.
+	fn draw(&mut self, display: &mut graphics::Graphics, alpha: f64);
+
+	/// The area which hurts a player who touches it.
+	fn damage_rectangle(&self) -> Rectangle;
+
+	/// The area which is struck by the player's projectiles.
+	fn collision_rectangle(&self) -> Rectangle;
+
+	/// How much damage the enemy deals to a player who touches it.
+	fn contact_damage(&self) -> units::HitPoints;
+
+	fn take_damage(&mut self, damage: units::HitPoints);
+	fn is_dead(&self) -> bool;
+}
+
+/// What remains of an enemy's health, & how it reacts to losing it.
+pub struct Health {
+	health:    units::HitPoints,
+	hurt_time: Option<units::Millis>, // time since the last hit, while it shows
+}
+
+impl Health {
+	pub fn new(health: units::HitPoints) -> Health {
+		Health { health: health, hurt_time: None }
+	}
+
+	pub fn take_damage(&mut self, damage: units::HitPoints) {
+		self.health    = cmp::max(self.health - damage, 0);
+		self.hurt_time = Some(units::Millis(0));
+	}
+
+	pub fn update(&mut self, elapsed_time: units::Millis) {
+		self.hurt_time = self.hurt_time
+			.map(|hurt_time| hurt_time + elapsed_time)
+			.and_then(|hurt_time| if hurt_time < HURT_TIME { Some(hurt_time) } else { None });
+	}
+
+	pub fn is_dead(&self) -> bool {
+		self.health == 0
+	}
+
+	/// Whether the enemy is hidden this frame, flashing after a hit.
+	pub fn is_flashing(&self) -> bool {
+		match self.hurt_time {
+			Some(units::Millis(hurt_time)) => {
+				let units::Millis(period) = FLASH_PERIOD;
+				(hurt_time / period) % 2 == 1
+			},
+			None => false,
+		}
+	}
+
+	/// How far the enemy is drawn from its position, shaking after a hit.
+	pub fn shake(&self) -> units::Game {
+		match self.hurt_time {
+			Some(units::Millis(hurt_time)) => {
+				let units::Millis(period) = SHAKE_PERIOD;
+				if (hurt_time / period) % 2 == 0 { SHAKE_DISTANCE } else { units::Game(0.0) - SHAKE_DISTANCE }
+			},
+			None => units::Game(0.0),
+		}
+	}
+}
+
+/// The kinds of enemy which can be placed on a map.
+#[derive(Clone,Copy,PartialEq,Eq)]
+pub enum Kind {
+	CaveBat,
+}
+
+impl Kind {
+	/// Looks up a kind of enemy by the name it is given in map files.
+	pub fn from_name(name: &str) -> Option<Kind> {
+		match name {
+			"bat" => Some(Kind::CaveBat),
+			_     => None,
+		}
+	}
+
+	/// Creates an enemy of this kind at `x`, `y`.
+	pub fn spawn(&self, display: &mut graphics::Graphics,
+	             x: units::Game, y: units::Game) -> Box<Enemy> {
+		match *self {
+			Kind::CaveBat => Box::new(CaveBat::new(display, x, y)) as Box<Enemy>,
+		}
+	}
+}
+
+#[test]
+fn test_health_shows_hits() {
+	let mut health = Health::new(3);
+	assert!(health.shake() == units::Game(0.0));
+
+	health.take_damage(2);
+	assert!(!health.is_dead());
+	assert!(health.shake() != units::Game(0.0));
+
+	health.update(HURT_TIME);
+	assert!(health.shake() == units::Game(0.0));
+	assert!(!health.is_flashing());
+
+	health.take_damage(2);
+	assert!(health.is_dead());
+}
@@ -1,83 +1,150 @@
 use std::cmp;
+use std::path::Path;
 use std::thread::sleep;
 use std::time::Duration;
 
-use enemies;
 use graphics;
 use input;
-use map;
-use player;
+use scenes::{self, Scene, Transition};
 use units;
-use units::{AsGame};
 
 use sdl2;
 use sdl2::event::Event;
 use sdl2::keyboard::Keycode;
 
 const TARGET_FRAMERATE: units::Fps  =  60;
-static MAX_FRAME_TIME: units::Millis =  units::Millis(5 * (1000 / TARGET_FRAMERATE as i64));
+static MAX_FRAME_TIME: units::Millis =  units::Millis(5 * 1000 / TARGET_FRAMERATE as i64);
+
+/// Wall-clock time is banked in milliseconds * `TARGET_FRAMERATE`,
+/// so that each tick costs exactly this much of it.
+const TICK_COST: i64 = 1000;
 
 pub static SCREEN_WIDTH:  units::Tile = units::Tile(20);
 pub static SCREEN_HEIGHT: units::Tile = units::Tile(15);
 
 /// An instance of the `rust-story` game with its own event loop.
+///
+/// The game itself is a stack of `scenes::Scene`s; the world sits at the
+/// bottom, while menus & overlays are pushed on top of it.
 pub struct Game<'engine> {
-	quote:  player::Player,
-	yatty:  enemies::CaveBat,
-	map:    map::Map,
+	scenes:  Vec<Box<Scene>>,
 
-	context:     &'engine sdl2::Sdl,
+	context:     Option<&'engine sdl2::Sdl>,
 	controller:  input::Input,
 	display:     graphics::Graphics<'engine>,
+	running:     bool,
+	ticks:       u64, // steps of the simulation run so far
 }
 
 impl<'e> Game<'e> {
 	/// Starts running this games event loop, note that this will block indefinitely.
 	/// This function will return to the caller when the escape key is pressed.
+	///
+	/// The game opens on its title screen.
 	pub fn new(context: &'e sdl2::Sdl) -> Game<'e> {
+		let mut game = Game::with_display(graphics::Graphics::new(context));
+		let title = scenes::Title::new(&mut game.display);
+
+		game.scenes.push(Box::new(title));
+		game.context = Some(context);
+		game
+	}
 
+	/// Builds the world on top of an already prepared `display`.
+	///
+	/// A game created this way has no SDL context: it cannot run its own
+	/// event loop, but it can be driven manually through `step()`.
+	pub fn with_display(mut display: graphics::Graphics<'e>) -> Game<'e> {
 		// initialize all major subsystems
-		let controller   = input::Input::new();
-		let mut display  = graphics::Graphics::new(context);
+		let controller = input::Input::new();
+		let gameplay   = scenes::Gameplay::new(&mut display);
 
 		Game {
-			map: map::Map::create_test_map(&mut display),
-			quote: player::Player::new(
-				&mut display,
-				(SCREEN_WIDTH  / units::Tile(2)).to_game(),
-				(SCREEN_HEIGHT / units::Tile(2)).to_game(),
-			),
-
-			yatty: enemies::CaveBat::new( &mut display,
-				(SCREEN_WIDTH / units::Tile(3)).to_game(),
-				(units::Tile(10)).to_game(),
-			),
+			scenes: vec![Box::new(gameplay) as Box<Scene>],
 
 			display:     display,
 			controller:  controller,
-			context:     context,
+			context:     None,
+			running:     false,
+			ticks:       0,
+		}
+	}
+
+	/// Runs the event loop until the game is quit.
+	///
+	/// Fails if SDL cannot be driven, e.g: the game was built w/o an
+	/// SDL context by `with_display()`.
+	pub fn start(&mut self) -> Result<(), String> {
+		self.event_loop()
+	}
+
+	/// Advances the world by exactly one tick w/o consulting SDL,
+	/// then draws the result to the display.
+	pub fn step(&mut self) {
+		self.tick();
+
+		self.display.clear_buffer();
+		self.draw(1.0);
+		self.display.switch_buffers();
+	}
+
+	/// Replaces the current map w/ the map file found at `path`.
+	pub fn load_map(&mut self, path: &Path) -> Result<(), String> {
+		let display = &mut self.display;
+		match self.scenes.iter_mut().filter_map(|scene| scene.as_gameplay()).next() {
+			Some(gameplay) => gameplay.load_map(display, path),
+			None => Err(format!("there is no world to load the map into")),
+		}
+	}
+
+	/// Replaces the current map w/ an original Cave Story stage.
+	pub fn load_stage(&mut self, pxm_path: &Path, pxa_path: &Path) -> Result<(), String> {
+		let display = &mut self.display;
+		match self.scenes.iter_mut().filter_map(|scene| scene.as_gameplay()).next() {
+			Some(gameplay) => gameplay.load_stage(display, pxm_path, pxa_path),
+			None => Err(format!("there is no world to load the stage into")),
 		}
 	}
 
-	pub fn start(&mut self) {
-		self.event_loop();
+	pub fn display(&self) -> &graphics::Graphics<'e> {
+		&self.display
+	}
+
+	pub fn controller(&mut self) -> &mut input::Input {
+		&mut self.controller
+	}
+
+	/// The world, if it is still on the scene stack.
+	pub fn gameplay(&mut self) -> Option<&mut scenes::Gameplay> {
+		self.scenes.iter_mut().filter_map(|scene| scene.as_gameplay()).next()
 	}
 
 	/// Polls current input events & dispatches them to the engine.
 	///
-	/// Then renders a snapshot of the world-state and then waits
-	/// until its next frame deadline.
-	fn event_loop(&mut self) {
+	/// Wall-clock time is banked in an accumulator which is then spent
+	/// in ticks of exactly 1/60th of a second, so the simulation is identical
+	/// regardless of how quickly frames are being drawn.
+	///
+	/// Then renders a snapshot of the world-state, interpolated between
+	/// the last two ticks, and waits until its next frame deadline.
+	fn event_loop(&mut self) -> Result<(), String> {
+		let context = try!(self.context.ok_or(format!("a game w/o an SDL context cannot be run")));
+		let mut timer      = try!(context.timer());
+		let mut event_pump = try!(context.event_pump());
+
+		// controllers must be kept open for their events to be delivered
+		// (SDL reports those already plugged in as having just been added.)
+		let pads = try!(context.game_controller());
+		let mut controllers = Vec::new();
+
 		// event loop control
 		let frame_delay          = units::Millis(1000 / TARGET_FRAMERATE as i64);
-		let mut last_update_time = units::Millis(self.get_ticks() as i64);
-	
-		let mut event_pump = self.context.event_pump().unwrap();
-		let mut running    = true;
-		
-		while running {
-			let start_time_ms = units::Millis(self.get_ticks() as i64);
-			self.controller.begin_new_frame();
+		let mut last_update_time = units::Millis(timer.ticks() as i64);
+		let mut accumulator      = 0;
+		self.running             = true;
+
+		while self.running {
+			let start_time_ms = units::Millis(timer.ticks() as i64);
 
 			// drain event queue once per frame
 			// ideally should do in separate task
@@ -90,62 +157,51 @@ impl<'e> Game<'e> {
 					Event::KeyUp { keycode, .. } => {
 						self.controller.key_up_event(keycode.unwrap());
 					},
+					Event::ControllerButtonDown { button, .. } => {
+						self.controller.button_down_event(button);
+					},
+					Event::ControllerButtonUp { button, .. } => {
+						self.controller.button_up_event(button);
+					},
+					Event::ControllerAxisMotion { axis, value, .. } => {
+						self.controller.axis_event(axis, value);
+					},
+					Event::ControllerDeviceAdded { which, .. } => {
+						match pads.open(which as u32) {
+							Ok(pad)  => controllers.push(pad),
+							Err(msg) => println!("could not open controller {}: {}", which, msg),
+						}
+					},
+					Event::ControllerDeviceRemoved { which, .. } => {
+						controllers.retain(|pad: &sdl2::controller::GameController| pad.instance_id() != which);
+					},
 					_ => {},
 				}
 			}
 
-			// Handle exit game
-			if self.controller.was_key_released(Keycode::Escape) {
-				running = false;
-			}
-
-			// Handle player movement
-			if self.controller.is_key_held(Keycode::Left)
-				&& self.controller.is_key_held(Keycode::Right) {
-
-				self.quote.stop_moving();
-			} else if self.controller.is_key_held(Keycode::Left) {
-				self.quote.start_moving_left();
-			} else if self.controller.is_key_held(Keycode::Right) {
-				self.quote.start_moving_right();
-			} else {
-				self.quote.stop_moving();
-			}
+			// bank how much time has passed since last frame
+			let current_time_ms = units::Millis(timer.ticks() as i64);
+			let elapsed_time    = current_time_ms - last_update_time;
 
-			// Handle player looking
-			if self.controller.is_key_held(Keycode::Up)
-				&& self.controller.is_key_held(Keycode::Down) {
-
-				self.quote.look_horizontal();
-			} else if self.controller.is_key_held(Keycode::Up) {
-				self.quote.look_up();
-			} else if self.controller.is_key_held(Keycode::Down) {
-				self.quote.look_down();
-			} else {
-				self.quote.look_horizontal();
-			}
+			let units::Millis(banked) = cmp::min(elapsed_time, MAX_FRAME_TIME);
+			accumulator      = accumulator + banked * TARGET_FRAMERATE as i64;
+			last_update_time = current_time_ms;
 
-			// Handle player jump
-			if self.controller.was_key_pressed(Keycode::Z) {
-				self.quote.start_jump();
-			} else if self.controller.was_key_released(Keycode::Z) {
-				self.quote.stop_jump();
+			// spend it in fixed increments
+			while accumulator >= TICK_COST && self.running {
+				self.tick();
+				accumulator = accumulator - TICK_COST;
 			}
 
-			// inform actors of how much time has passed since last frame
-			let current_time_ms = units::Millis(self.get_ticks() as i64);
-			let elapsed_time    = current_time_ms - last_update_time;
-			
-			self.update(cmp::min(elapsed_time, MAX_FRAME_TIME));
-			last_update_time = current_time_ms;
-
 			// draw
+			let alpha = accumulator as f64 / TICK_COST as f64;
+
 			self.display.clear_buffer(); // clear back-buffer
-			self.draw();
+			self.draw(alpha);
 			self.display.switch_buffers();
 
 			// throttle event-loop based on iteration time vs frame deadline
-			let iter_time = units::Millis(self.get_ticks() as i64) - start_time_ms;
+			let iter_time = units::Millis(timer.ticks() as i64) - start_time_ms;
 			let next_frame_time: u64 = if frame_delay > iter_time { 
 				let (units::Millis(fd), units::Millis(it)) = (frame_delay, iter_time);
 				(fd - it) as u64
@@ -163,40 +219,248 @@ impl<'e> Game<'e> {
 			
 		}
 
+		Ok(())
 	}
 
-    // TODO: use time::* etc?
-    fn get_ticks(&mut self) -> u32 {
-        self.context.timer().unwrap().ticks()
-    }
+	/// Runs a single tick of the simulation.
+	///
+	/// Input which arrived since the previous tick is applied first, the
+	/// toggle states of the controller are then reset so that a single
+	/// key press is only ever observed by one tick.
+	///
+	/// Only the topmost scene receives input & is updated.
+	fn tick(&mut self) {
+		// Handle exit game
+		if self.controller.was_key_released(Keycode::Escape) {
+			self.running = false;
+		}
 
-	/// Instructs our actors to draw their current state to the screen.
-	fn draw(&mut self) {
-		// background
-		self.map.draw_background(&mut self.display);
-		self.map.draw_sprites(&mut self.display);
+		let transition = match self.scenes.last_mut() {
+			Some(scene) => scene.handle_input(&self.controller, &mut self.display),
+			None        => Transition::Quit,
+		};
+		self.apply(transition);
 
-		// foreground
-		self.quote.draw(&mut self.display);
-		self.yatty.draw(&mut self.display);
-		self.map.draw(&mut self.display);
+		let transition = match self.scenes.last_mut() {
+			Some(scene) => scene.update(timestep(self.ticks), &mut self.display),
+			None        => Transition::Quit,
+		};
+		self.apply(transition);
 
-		// ui
-		self.quote.draw_hud(&mut self.display);
+		self.controller.begin_new_frame();
+		self.ticks += 1;
 	}
 
-	/// Passes the current time in milliseconds to our underlying actors.
-	fn update(&mut self, elapsed_time: units::Millis) {
-		self.map.update(elapsed_time);
-		self.quote.update(elapsed_time, &self.map);
-		self.yatty.update(elapsed_time, self.quote.center_x());
+	/// Rearranges the scene stack as requested by its topmost scene.
+	fn apply(&mut self, transition: Transition) {
+		match transition {
+			Transition::None => {},
+			Transition::Push(scene) => self.scenes.push(scene),
+			Transition::Replace(scene) => {
+				self.scenes.pop();
+				self.scenes.push(scene);
+			},
+			Transition::Pop => {
+				self.scenes.pop();
+				match self.scenes.last_mut() {
+					Some(scene) => scene.resume(&mut self.display),
+					None        => self.running = false,
+				}
+			},
+			Transition::Step => self.step_beneath(),
+			Transition::Quit => self.running = false,
+		}
+	}
+
+	/// Runs a single tick of the scene beneath the topmost one, which
+	/// sees the current input just as it would if it were on top.
+	/// Should that scene want to rearrange the stack, the topmost scene
+	/// is removed first.
+	fn step_beneath(&mut self) {
+		let len = self.scenes.len();
+		if len < 2 { return; }
+
+		let mut transition = self.scenes[len - 2].handle_input(&self.controller, &mut self.display);
+		if let Transition::None = transition {
+			transition = self.scenes[len - 2].update(timestep(self.ticks), &mut self.display);
+		}
+
+		match transition {
+			Transition::None => {},
+			transition => {
+				self.scenes.pop();
+				self.apply(transition);
+			},
+		}
+	}
+
+	/// Draws the topmost scene, along w/ any scenes visible beneath it.
+	///
+	/// Scenes beneath the topmost one are frozen, so they are drawn
+	/// exactly where they stopped rather than interpolated by `alpha`.
+	fn draw(&mut self, alpha: f64) {
+		let bottom = self.scenes.iter().rposition(|scene| !scene.is_overlay()).unwrap_or(0);
+		let top    = self.scenes.len().saturating_sub(1);
+		for (idx, scene) in self.scenes.iter_mut().enumerate().skip(bottom) {
+			scene.draw(&mut self.display, if idx == top { alpha } else { 1.0 });
+		}
+	}
+}
+
+/// The length of the simulation's `tick`th step.
+///
+/// A 60th of a second is not a whole number of milliseconds: steps are
+/// 16 or 17ms long, arranged so that every 60 of them span exactly one second.
+pub fn timestep(tick: u64) -> units::Millis {
+	let start = |tick: u64| (tick * 1000 / TARGET_FRAMERATE) as i64;
+	units::Millis(start(tick + 1) - start(tick))
+}
+
+#[test]
+fn test_timesteps_span_whole_seconds() {
+	let steps: Vec<_> = (0..120).map(timestep).collect();
+	assert!(steps.iter().all(|&step| step == units::Millis(16) || step == units::Millis(17)));
+
+	let units::Millis(total) = steps.into_iter().fold(units::Millis(0), |total, step| total + step);
+	assert_eq!(total, 2000);
+}
 
-		let collided =
-			self.yatty.damage_rectangle()
-			    .collides_with(&self.quote.damage_rectangle());
+#[test]
+fn test_headless_game_steps() {
+	let mut story = Game::with_display(graphics::Graphics::headless());
+	for _ in 0..10 {
+		story.step();
+	}
+
+	let backend = story.display().headless_backend().unwrap();
+	assert_eq!(backend.frames(), 10);
+	assert!(backend.blits().len() > 0);
+}
+
+#[test]
+fn test_headless_game_cannot_start() {
+	let mut story = Game::with_display(graphics::Graphics::headless());
+	assert!(story.start().is_err());
+}
 
-		if collided {
-			self.quote.take_damage();
+#[test]
+fn test_replay_reproduces_movement() {
+	use units::AsGame;
+
+	let mut live = Game::with_display(graphics::Graphics::headless());
+	live.controller().start_recording();
+	for tick in 0..120 {
+		match tick {
+			5  => live.controller().key_down_event(Keycode::Right),
+			20 => live.controller().key_down_event(Keycode::Z),
+			45 => live.controller().key_up_event(Keycode::Z),
+			70 => live.controller().key_up_event(Keycode::Right),
+			_  => {},
 		}
+		live.step();
 	}
+
+	let recording = live.controller().stop_recording().unwrap();
+	let mut replay = Game::with_display(graphics::Graphics::headless());
+	replay.controller().start_playback(recording);
+	for _ in 0..120 {
+		replay.step();
+	}
+
+	let position = live.gameplay().unwrap().player().position();
+	assert!(position == replay.gameplay().unwrap().player().position());
+	assert!(position.0 != (SCREEN_WIDTH / units::Tile(2)).to_game());
+}
+
+#[test]
+fn test_overlays_freeze_the_world() {
+	let mut story = Game::with_display(graphics::Graphics::headless());
+	story.controller().key_down_event(Keycode::Q);
+	story.step();
+	story.controller().key_up_event(Keycode::Q);
+	assert_eq!(story.scenes.len(), 2);
+
+	// the world is still drawn beneath the inventory, but does not move
+	let position = story.gameplay().unwrap().player().position();
+	for _ in 0..30 {
+		story.step();
+	}
+	assert!(story.gameplay().unwrap().player().position() == position);
+	assert!(story.display().headless_backend().unwrap().fills().len() == 1);
+
+	story.controller().key_down_event(Keycode::Q);
+	story.step();
+	assert_eq!(story.scenes.len(), 1);
+}
+
+#[test]
+fn test_pause_steps_one_frame() {
+	let mut story = Game::with_display(graphics::Graphics::headless());
+	story.controller().key_down_event(Keycode::Right);
+	for _ in 0..10 {
+		story.step();
+	}
+
+	story.controller().key_down_event(Keycode::P);
+	story.step();
+	story.controller().key_up_event(Keycode::P);
+
+	let paused = story.gameplay().unwrap().player().position();
+	for _ in 0..10 {
+		story.step();
+	}
+	assert!(story.gameplay().unwrap().player().position() == paused);
+
+	// each press of the step key advances the world by exactly one tick
+	story.controller().key_down_event(Keycode::Period);
+	story.step();
+	story.controller().key_up_event(Keycode::Period);
+	let stepped = story.gameplay().unwrap().player().position();
+	assert!(stepped != paused);
+
+	story.step();
+	assert!(story.gameplay().unwrap().player().position() == stepped);
+	assert_eq!(story.scenes.len(), 2);
+}
+
+#[test]
+fn test_stepping_feeds_input_to_the_world() {
+	let mut story = Game::with_display(graphics::Graphics::headless());
+	story.controller().key_down_event(Keycode::P);
+	story.step();
+	story.controller().key_up_event(Keycode::P);
+	let paused = story.gameplay().unwrap().player().position();
+
+	// keys held while paused move the player, one tick at a time
+	story.controller().key_down_event(Keycode::Right);
+	story.step();
+	assert!(story.gameplay().unwrap().player().position() == paused);
+
+	story.controller().key_down_event(Keycode::Period);
+	story.step();
+	story.controller().key_up_event(Keycode::Period);
+	assert!(story.gameplay().unwrap().player().position().0 > paused.0);
+	assert_eq!(story.scenes.len(), 2);
+}
+
+#[test]
+fn test_paused_world_is_drawn_still() {
+	let mut story = Game::with_display(graphics::Graphics::headless());
+	story.controller().key_down_event(Keycode::Right);
+	for _ in 0..10 {
+		story.step();
+	}
+
+	story.controller().key_down_event(Keycode::P);
+	story.step();
+
+	// however far between ticks the frame falls, the player is in the same place
+	let mut frames = [0.0, 0.5].iter().map(|&alpha| {
+		story.display.clear_buffer();
+		story.draw(alpha);
+		story.display().headless_backend().unwrap().blits().iter()
+			.map(|blit| blit.dest_rect).collect::<Vec<_>>()
+	}).collect::<Vec<_>>();
+
+	assert!(frames.pop() == frames.pop());
 }
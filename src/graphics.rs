@@ -4,25 +4,133 @@ use units::{AsPixel};
 
 use sdl2::{self, rect, surface};
 use sdl2::pixels::Color;
-use sdl2::render::{Renderer, Texture};
+use sdl2::render::{BlendMode, Renderer, Texture};
 
 use std::collections::hash_map::{HashMap, Entry};
 use std::path::Path;
 
+/// Any surface which the game can paint its sprites onto.
+///
+/// Sprites are identified by the path of the bitmap they were loaded from,
+/// a backend is expected to cache them on `load_image` and then copy regions
+/// of that bitmap onto its back-buffer whenever `blit_surface` is called.
+pub trait Backend {
+	fn load_image(&mut self, file_path: String, transparent_black: bool);
+	fn blit_surface(&mut self, src_id: &str, src_rect: &rect::Rect, dest_rect: &rect::Rect);
+	/// The width & height of a loaded image, if they are known.
+	fn image_size(&self, file_path: &str) -> Option<(u32, u32)>;
+	/// Paints a solid rectangle, blended w/ whatever is beneath it by `color`'s alpha.
+	fn fill_rect(&mut self, dest_rect: &rect::Rect, color: Color);
+	fn clear_buffer(&mut self);
+	fn switch_buffers(&mut self);
+
+	/// Allows tests to inspect the backend if it is rendering offscreen.
+	fn as_headless(&self) -> Option<&HeadlessBackend> { None }
+}
+
 /// Acts as a buffer to the underlying display
+///
+/// While a camera is set, blits are given in map coordinates and are
+/// shifted so that the camera's position is the top-left of the screen.
 pub struct Graphics<'g> {
-	cache:  HashMap<String, Texture>,
-	screen: Renderer<'g>,
+	backend: Box<Backend + 'g>,
+	camera:  (units::Game, units::Game),
 }
 
 impl<'g> Graphics<'g> {
 	/// Prepare the display for rendering
 	pub fn new(context: &sdl2::Sdl) -> Graphics<'g> {
+		Graphics::from_backend(Box::new(SdlBackend::new(context)))
+	}
+
+	/// Prepares an offscreen display which does not require a window.
+	pub fn headless() -> Graphics<'g> {
+		Graphics::from_backend(Box::new(HeadlessBackend::new()))
+	}
+
+	pub fn from_backend(backend: Box<Backend + 'g>) -> Graphics<'g> {
+		Graphics { backend: backend, camera: (units::Game(0.0), units::Game(0.0)) }
+	}
+
+	/// Caches the bitmap found at `file_path` ...
+	/// The filename can then be used to fetch a handle to the loaded
+	/// texture at a later occasion.
+	///
+	/// Panics if the resource cannot be loaded for any reason ...
+	pub fn load_image(&mut self,
+	                  file_path: String,
+	                  transparent_black: bool) {
+		self.backend.load_image(file_path, transparent_black);
+	}
+
+	/// The width & height, in pixels, of the image loaded from `file_path`.
+	pub fn image_size(&self, file_path: &str) -> Option<(u32, u32)> {
+		self.backend.image_size(file_path)
+	}
+
+	pub fn blit_surface(&mut self,
+	                    src_id: &str,
+	                    src_rect:  &rect::Rect,
+	                    dest_rect: &rect::Rect) {
+		let (cam_x, cam_y) = self.camera;
+		let (units::Pixel(ofs_x), units::Pixel(ofs_y)) = (cam_x.to_pixel(), cam_y.to_pixel());
+
+		let mut dest_rect = *dest_rect;
+		dest_rect.offset(-ofs_x, -ofs_y);
+		self.backend.blit_surface(src_id, src_rect, &dest_rect);
+	}
+
+	/// Paints a solid rectangle, e.g: to fade the screen out.
+	pub fn fill_rect(&mut self, dest_rect: &rect::Rect, color: Color) {
+		let (cam_x, cam_y) = self.camera;
+		let (units::Pixel(ofs_x), units::Pixel(ofs_y)) = (cam_x.to_pixel(), cam_y.to_pixel());
+
+		let mut dest_rect = *dest_rect;
+		dest_rect.offset(-ofs_x, -ofs_y);
+		self.backend.fill_rect(&dest_rect, color);
+	}
+
+	/// Draws subsequent blits relative to a camera placed at `(x, y)` on the map.
+	pub fn set_camera(&mut self, x: units::Game, y: units::Game) {
+		self.camera = (x, y);
+	}
+
+	/// Draws subsequent blits in screen-space.
+	pub fn reset_camera(&mut self) {
+		self.camera = (units::Game(0.0), units::Game(0.0));
+	}
+
+	pub fn camera(&self) -> (units::Game, units::Game) {
+		self.camera
+	}
+
+	pub fn switch_buffers(&mut self) {
+		self.backend.switch_buffers();
+	}
+
+	pub fn clear_buffer(&mut self) {
+		self.backend.clear_buffer();
+	}
+
+	/// Returns the offscreen backend if this display is not backed by a window.
+	pub fn headless_backend(&self) -> Option<&HeadlessBackend> {
+		self.backend.as_headless()
+	}
+}
+
+/// Renders to a window using SDL's software renderer.
+pub struct SdlBackend<'g> {
+	cache:  HashMap<String, Texture>,
+	screen: Renderer<'g>,
+}
+
+impl<'g> SdlBackend<'g> {
+	pub fn new(context: &sdl2::Sdl) -> SdlBackend<'g> {
 		// boot the renderer
-		let (units::Pixel(w), units::Pixel(h)) = 
+		let (units::Pixel(w), units::Pixel(h)) =
 			(game::SCREEN_WIDTH.to_pixel(), game::SCREEN_HEIGHT.to_pixel());
-	
-       
+
+
         let video            = context.video().unwrap();
         let mut window_proto = video.window("rust-story v0.0", w as u32, h as u32);
         let current_mode     = window_proto.position_centered()
@@ -40,25 +148,22 @@ impl<'g> Graphics<'g> {
             .unwrap();
 
 		// strap it to graphics subsystem
-		let graphics = Graphics {
+		let backend = SdlBackend {
 			cache:  HashMap::new(),
 			screen: renderer,
 		};
 
         context.mouse().show_cursor(true);
-		return graphics;
+		return backend;
 	}
 
+	pub fn borrow_res(&mut self, file_path: String) -> &mut Texture {
+		self.cache.get_mut(&file_path).unwrap()
+	}
+}
 
-	/// Caches the bitmap found at `file_path` ...
-	/// The filename can then be used to fetch a handle to the loaded
-	/// texture at a later occasion.
-	///
-	/// Panics if the resource cannot be loaded for any reason ...
-	pub fn load_image(&mut self, 
-	                  file_path: String, 
-	                  transparent_black: bool) {
-		
+impl<'g> Backend for SdlBackend<'g> {
+	fn load_image(&mut self, file_path: String, transparent_black: bool) {
 		// Retrieve a handle or generate a new one if it exists already.
 		// Load sprite
 		let sprite_path = Path::new(&file_path[..]);
@@ -90,24 +195,301 @@ impl<'g> Graphics<'g> {
 		};
 	}
 
-	pub fn borrow_res(&mut self, file_path: String) -> &mut Texture {
-		self.cache.get_mut(&file_path).unwrap()
-	}
+	fn blit_surface(&mut self,
+	                src_id: &str,
+	                src_rect:  &rect::Rect,
+	                dest_rect: &rect::Rect) {
 
-	pub fn blit_surface(&mut self,
-	                    src_id: &str,
-	                    src_rect:  &rect::Rect,
-	                    dest_rect: &rect::Rect) {
-	
 		let src = &mut self.cache.get_mut(src_id).unwrap();
 		let _ = self.screen.copy(src, Some(*src_rect), Some(*dest_rect));
 	}
 
-	pub fn switch_buffers(&mut self) {
+	fn image_size(&self, file_path: &str) -> Option<(u32, u32)> {
+		self.cache.get(file_path).map(|texture| {
+			let query = texture.query();
+			(query.width, query.height)
+		})
+	}
+
+	fn fill_rect(&mut self, dest_rect: &rect::Rect, color: Color) {
+		self.screen.set_blend_mode(BlendMode::Blend);
+		self.screen.set_draw_color(color);
+		let _ = self.screen.fill_rect(*dest_rect);
+
+		self.screen.set_draw_color(Color::RGB(0, 0, 0));
+		self.screen.set_blend_mode(BlendMode::None);
+	}
+
+	fn switch_buffers(&mut self) {
 		self.screen.present();
 	}
 
-	pub fn clear_buffer(&mut self) {
+	fn clear_buffer(&mut self) {
 		let _ = self.screen.clear();
 	}
 }
+
+/// A single copy from a cached bitmap onto the back-buffer.
+#[derive(Clone)]
+pub struct Blit {
+	pub src_id:    String,
+	pub src_rect:  rect::Rect,
+	pub dest_rect: rect::Rect,
+}
+
+/// A solid rectangle painted onto the back-buffer.
+#[derive(Clone)]
+pub struct Fill {
+	pub dest_rect: rect::Rect,
+	pub color:     Color,
+}
+
+/// An in-memory bitmap w/ pixels stored as `0xRRGGBB`.
+///
+/// Images which were never given pixel data are painted in a solid color
+/// derived from their path; so they can still be told apart when rasterized.
+struct Image {
+	width:   u32,
+	height:  u32,
+	pixels:  Option<Vec<u32>>,
+	color:   u32,
+	transparent_black: bool,
+}
+
+impl Image {
+	fn pixel_at(&self, x: i32, y: i32) -> Option<u32> {
+		let color = match self.pixels {
+			Some(ref pixels) => {
+				if x < 0 || y < 0 || x as u32 >= self.width || y as u32 >= self.height {
+					return None;
+				}
+
+				pixels[(y as u32 * self.width + x as u32) as usize]
+			},
+			None => self.color,
+		};
+
+		if self.transparent_black && color == 0 { None } else { Some(color) }
+	}
+}
+
+/// Renders without a window: blits are recorded for inspection and can be
+/// painted onto a pixel buffer on demand.
+pub struct HeadlessBackend {
+	images:  HashMap<String, Image>,
+	blits:   Vec<Blit>,
+	fills:   Vec<Fill>,
+	frames:  usize,
+
+	width:   u32,
+	height:  u32,
+}
+
+impl HeadlessBackend {
+	pub fn new() -> HeadlessBackend {
+		let (units::Pixel(w), units::Pixel(h)) =
+			(game::SCREEN_WIDTH.to_pixel(), game::SCREEN_HEIGHT.to_pixel());
+
+		HeadlessBackend {
+			images: HashMap::new(),
+			blits:  Vec::new(),
+			fills:  Vec::new(),
+			frames: 0,
+
+			width:  w as u32,
+			height: h as u32,
+		}
+	}
+
+	/// Supplies the pixel data for `file_path` rather than reading it from disk.
+	/// (Pixels are stored row-major as `0xRRGGBB`.)
+	pub fn insert_image(&mut self, file_path: String,
+	                    width: u32, height: u32, pixels: Vec<u32>) {
+		assert_eq!(pixels.len(), (width * height) as usize);
+
+		let color = placeholder_color(&file_path[..]);
+		self.images.insert(file_path, Image {
+			width: width, height: height,
+			pixels: Some(pixels), color: color,
+			transparent_black: false,
+		});
+	}
+
+	/// All blits made since the back-buffer was last cleared.
+	pub fn blits(&self) -> &[Blit] {
+		&self.blits[..]
+	}
+
+	/// Rectangles filled since the back-buffer was last cleared.
+	pub fn fills(&self) -> &[Fill] {
+		&self.fills[..]
+	}
+
+	/// The number of times the back-buffer has been presented.
+	pub fn frames(&self) -> usize {
+		self.frames
+	}
+
+	pub fn size(&self) -> (u32, u32) {
+		(self.width, self.height)
+	}
+
+	/// Paints the recorded blits, in order, onto a black buffer of `size()`.
+	/// Destination rectangles are sampled nearest-neighbor from their source.
+	/// (Filled rectangles are not painted.)
+	pub fn rasterize(&self) -> Vec<u32> {
+		let mut buffer = vec![0u32; (self.width * self.height) as usize];
+
+		for blit in self.blits.iter() {
+			let image = match self.images.get(&blit.src_id) {
+				Some(image) => image,
+				None => panic!("blit from an image which was never loaded: {}", blit.src_id),
+			};
+
+			let (dw, dh) = (blit.dest_rect.width(), blit.dest_rect.height());
+			let (sw, sh) = (blit.src_rect.width(), blit.src_rect.height());
+			if dw == 0 || dh == 0 { continue; }
+
+			for dy in 0..dh {
+				let y = blit.dest_rect.y() + dy as i32;
+				if y < 0 || y as u32 >= self.height { continue; }
+
+				for dx in 0..dw {
+					let x = blit.dest_rect.x() + dx as i32;
+					if x < 0 || x as u32 >= self.width { continue; }
+
+					let sx = blit.src_rect.x() + (dx * sw / dw) as i32;
+					let sy = blit.src_rect.y() + (dy * sh / dh) as i32;
+					match image.pixel_at(sx, sy) {
+						Some(color) => { buffer[(y as u32 * self.width + x as u32) as usize] = color; },
+						None => {},
+					}
+				}
+			}
+		}
+
+		buffer
+	}
+}
+
+impl Backend for HeadlessBackend {
+	fn load_image(&mut self, file_path: String, transparent_black: bool) {
+		match self.images.entry(file_path.clone()) {
+			Entry::Vacant(entry) => {
+				entry.insert(Image {
+					width: 0, height: 0, pixels: None,
+					color: placeholder_color(&file_path[..]),
+					transparent_black: transparent_black,
+				});
+			},
+
+			Entry::Occupied(mut entry) => {
+				entry.get_mut().transparent_black = transparent_black;
+			},
+		};
+	}
+
+	fn blit_surface(&mut self,
+	                src_id: &str,
+	                src_rect:  &rect::Rect,
+	                dest_rect: &rect::Rect) {
+		assert!(self.images.contains_key(src_id), "sprite was never loaded: {}", src_id);
+
+		self.blits.push(Blit {
+			src_id:    src_id.to_string(),
+			src_rect:  *src_rect,
+			dest_rect: *dest_rect,
+		});
+	}
+
+	/// Images which were never given pixel data have no size.
+	fn image_size(&self, file_path: &str) -> Option<(u32, u32)> {
+		self.images.get(file_path)
+			.and_then(|image| image.pixels.as_ref().map(|_| (image.width, image.height)))
+	}
+
+	fn fill_rect(&mut self, dest_rect: &rect::Rect, color: Color) {
+		self.fills.push(Fill { dest_rect: *dest_rect, color: color });
+	}
+
+	fn switch_buffers(&mut self) {
+		self.frames += 1;
+	}
+
+	fn clear_buffer(&mut self) {
+		self.blits.clear();
+		self.fills.clear();
+	}
+
+	fn as_headless(&self) -> Option<&HeadlessBackend> { Some(self) }
+}
+
+/// Hashes `file_path` (FNV-1a) into a non-black `0xRRGGBB` color.
+fn placeholder_color(file_path: &str) -> u32 {
+	let mut hash: u32 = 0x811c9dc5;
+	for byte in file_path.bytes() {
+		hash = (hash ^ byte as u32).wrapping_mul(0x01000193);
+	}
+
+	(hash & 0xffffff) | 0x010101
+}
+
+#[test]
+fn test_headless_records_blits() {
+	let mut display = Graphics::headless();
+	display.load_image(format!("assets/base/MyChar.bmp"), true);
+
+	display.clear_buffer();
+	display.blit_surface("assets/base/MyChar.bmp",
+	                     &rect::Rect::new(0, 0, 32, 32),
+	                     &rect::Rect::new(64, 96, 32, 32));
+	display.switch_buffers();
+
+	let backend = display.headless_backend().unwrap();
+	assert_eq!(backend.frames(), 1);
+	assert_eq!(backend.blits().len(), 1);
+	assert_eq!(backend.blits()[0].dest_rect.x(), 64);
+}
+
+#[test]
+fn test_camera_offsets_blits() {
+	let mut display = Graphics::headless();
+	display.load_image(format!("assets/base/MyChar.bmp"), true);
+
+	display.set_camera(units::Game(100.0), units::Game(50.0));
+	display.blit_surface("assets/base/MyChar.bmp",
+	                     &rect::Rect::new(0, 0, 32, 32),
+	                     &rect::Rect::new(164, 96, 32, 32));
+
+	display.reset_camera();
+	display.blit_surface("assets/base/MyChar.bmp",
+	                     &rect::Rect::new(0, 0, 32, 32),
+	                     &rect::Rect::new(164, 96, 32, 32));
+
+	let blits = display.headless_backend().unwrap().blits();
+	assert_eq!((blits[0].dest_rect.x(), blits[0].dest_rect.y()), (64, 46));
+	assert_eq!((blits[1].dest_rect.x(), blits[1].dest_rect.y()), (164, 96));
+}
+
+#[test]
+fn test_headless_rasterizes_blits() {
+	let mut backend = HeadlessBackend::new();
+	backend.insert_image(format!("checker"), 2, 2,
+	                     vec![0xff0000, 0x000000,
+	                          0x000000, 0x00ff00]);
+	backend.load_image(format!("checker"), true);
+
+	backend.blit_surface("checker",
+	                     &rect::Rect::new(0, 0, 2, 2),
+	                     &rect::Rect::new(10, 10, 4, 4));
+
+	let (width, _) = backend.size();
+	let pixels = backend.rasterize();
+	let at = |x: u32, y: u32| pixels[(y * width + x) as usize];
+
+	assert_eq!(at(10, 10), 0xff0000); // scaled 2x
+	assert_eq!(at(11, 11), 0xff0000);
+	assert_eq!(at(12, 10), 0x000000); // keyed out
+	assert_eq!(at(13, 13), 0x00ff00);
+	assert_eq!(at(0, 0),   0x000000); // untouched
+}
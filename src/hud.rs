@@ -0,0 +1,347 @@
+use std::cmp;
+
+use game;
+use graphics;
+use number_sprite::NumberSprite;
+use sprite::{Drawable, Sprite};
+use weapons;
+
+use units;
+use units::AsGame;
+
+static HEALTH_BAR_X: units::Tile           = units::Tile(2);
+static HEALTH_BAR_Y: units::Tile           = units::Tile(2);
+static HEALTH_BAR_OFS_X: units::HalfTile   = units::HalfTile(0);
+static HEALTH_BAR_OFS_Y: units::HalfTile   = units::HalfTile(5);
+static HEALTH_BAR_W: units::HalfTile       = units::HalfTile(8);
+static HEALTH_BAR_H: units::HalfTile       = units::HalfTile(1);
+
+static HEALTH_FILL_X: units::HalfTile      = units::HalfTile(7);
+static HEALTH_FILL_Y: units::HalfTile      = units::HalfTile(4);
+static HEALTH_FILL_OFS_X: units::HalfTile  = units::HalfTile(0);
+static HEALTH_FILL_OFS_Y: units::HalfTile  = units::HalfTile(3);
+
+static HEALTH_DAMAGE_OFS_X: units::HalfTile  = units::HalfTile(0);
+static HEALTH_DAMAGE_OFS_Y: units::HalfTile  = units::HalfTile(4);
+
+static HEALTH_NUMBER_X: units::Tile  = units::Tile(3);
+static HEALTH_NUMBER_Y: units::Tile  = units::Tile(2);
+
+static FILL_SHIFT: units::Game         = units::Game(2.0);
+static HEALTH_FILL_W: units::HalfTile  = units::HalfTile(5);
+static HEALTH_FILL_H: units::HalfTile  = units::HalfTile(1);
+
+static LEVEL_Y: units::HalfTile              = units::HalfTile(3);
+static LEVEL_LABEL_X: units::HalfTile        = units::HalfTile(4);
+static LEVEL_LABEL_OFS_X: units::HalfTile    = units::HalfTile(10);
+static LEVEL_LABEL_OFS_Y: units::HalfTile    = units::HalfTile(10);
+static LEVEL_LABEL_W: units::HalfTile        = units::HalfTile(2);
+static LEVEL_NUMBER_X: units::HalfTile       = units::HalfTile(6);
+
+static EXPERIENCE_BAR_X: units::HalfTile       = units::HalfTile(7);
+static EXPERIENCE_BAR_OFS_X: units::HalfTile   = units::HalfTile(0);
+static EXPERIENCE_BAR_OFS_Y: units::HalfTile   = units::HalfTile(9);
+static EXPERIENCE_FILL_OFS_Y: units::HalfTile  = units::HalfTile(10);
+static EXPERIENCE_MAX_OFS_X: units::HalfTile   = units::HalfTile(5);
+static EXPERIENCE_BAR_W: units::HalfTile       = units::HalfTile(5);
+static EXPERIENCE_BAR_H: units::HalfTile       = units::HalfTile(1);
+
+static WEAPON_ICON_X: units::HalfTile  = units::HalfTile(4);
+static WEAPON_ICON_Y: units::HalfTile  = units::HalfTile(1);
+static AMMO_X: units::HalfTile         = units::HalfTile(9);
+static AMMO_Y: units::HalfTile         = units::HalfTile(1);
+static MAX_AMMO_Y: units::HalfTile     = units::HalfTile(2);
+
+static PROMPT_OFS_X: units::Game  = units::Game(304.0);
+static PROMPT_OFS_Y: units::Game  = units::Game(96.0);
+static PROMPT_W: units::Game      = units::Game(184.0);
+static PROMPT_H: units::Game      = units::Game(64.0);
+
+static CURSOR_OFS_X: units::Game  = units::Game(224.0);
+static CURSOR_OFS_Y: units::Game  = units::Game(176.0);
+static CURSOR_YES_X: units::Game  = units::Game(8.0);
+static CURSOR_NO_X:  units::Game  = units::Game(92.0);
+static CURSOR_Y:     units::Game  = units::Game(16.0);
+
+/// How long the lost portion of the bar lingers before it starts to drain.
+static DRAIN_DELAY: units::Millis    = units::Millis(500);
+static DRAIN_SPEED: units::Velocity  = units::Velocity(0.05);
+
+/// The player's health, as shown in the top-left corner of the screen.
+///
+/// Health which was just lost is shown as a lighter "damage" segment at the
+/// end of the bar; it lingers for a moment then drains away.
+pub struct HealthBar {
+	frame:   Sprite,
+	fill:    Sprite,
+	damage:  Sprite,
+	number:  NumberSprite,
+
+	health:      units::HitPoints,
+	max_health:  units::HitPoints,
+	shown:       units::HitPoints, // value of `number`
+
+	// width of the damage segment, measured from the left of the bar
+	damage_width:  units::Game,
+	damage_time:   units::Millis,
+}
+
+impl HealthBar {
+	pub fn new(graphics: &mut graphics::Graphics,
+	           health: units::HitPoints, max_health: units::HitPoints) -> HealthBar {
+		let path = format!("assets/base/TextBox.bmp");
+		let fill_size = (full_width(), HEALTH_FILL_H.to_game());
+
+		let mut bar = HealthBar {
+			frame: Sprite::new(graphics, (HEALTH_BAR_OFS_X, HEALTH_BAR_OFS_Y),
+			                   (HEALTH_BAR_W, HEALTH_BAR_H), path.clone()),
+			fill: Sprite::new(graphics, (HEALTH_FILL_OFS_X, HEALTH_FILL_OFS_Y),
+			                  fill_size, path.clone()),
+			damage: Sprite::new(graphics, (HEALTH_DAMAGE_OFS_X, HEALTH_DAMAGE_OFS_Y),
+			                    fill_size, path),
+			number: NumberSprite::new(graphics, health),
+
+			health: health,
+			max_health: max_health,
+			shown: health,
+
+			damage_width: units::Game(0.0),
+			damage_time:  units::Millis(0),
+		};
+
+		bar.damage_width = bar.fill_width();
+		bar.fill.set_width(bar.damage_width);
+		bar
+	}
+
+	/// Shows `health` on the bar. Any health lost is left behind as a
+	/// damage segment, health gained is shown immediately.
+	pub fn set_health(&mut self, health: units::HitPoints) {
+		let lost = health < self.health;
+		self.health = cmp::max(health, 0);
+
+		let width = self.fill_width();
+		self.fill.set_width(width);
+
+		if lost {
+			self.damage_time = units::Millis(0);
+		} else if width > self.damage_width {
+			self.damage_width = width;
+		}
+	}
+
+	/// The width of the bar's damage segment, incl. the current health.
+	pub fn damage_width(&self) -> units::Game {
+		self.damage_width
+	}
+
+	pub fn update(&mut self, elapsed_time: units::Millis) {
+		let width = self.fill_width();
+		if self.damage_width <= width { return; }
+
+		self.damage_time = self.damage_time + elapsed_time;
+		if self.damage_time < DRAIN_DELAY { return; }
+
+		let drained = self.damage_width - (DRAIN_SPEED * elapsed_time);
+		self.damage_width = if drained < width { width } else { drained };
+	}
+
+	pub fn draw(&mut self, display: &mut graphics::Graphics) {
+		self.frame.draw(display, (HEALTH_BAR_X, HEALTH_BAR_Y));
+
+		// the fill is painted over the damage segment
+		if self.damage_width > self.fill_width() {
+			self.damage.set_width(self.damage_width);
+			self.damage.draw(display, (HEALTH_FILL_X, HEALTH_FILL_Y));
+		}
+
+		if self.health > 0 {
+			self.fill.draw(display, (HEALTH_FILL_X, HEALTH_FILL_Y));
+		}
+
+		// only rebuild the digits when they've changed
+		if self.shown != self.health {
+			self.number = NumberSprite::new(display, self.health);
+			self.shown  = self.health;
+		}
+
+		self.number.draw(display, (HEALTH_NUMBER_X, HEALTH_NUMBER_Y));
+	}
+
+	/// Width of the fill for the current health.
+	fn fill_width(&self) -> units::Game {
+		let ratio = self.health as f64 / self.max_health as f64;
+		full_width() * units::Game(ratio)
+	}
+}
+
+/// The current weapon's level & its progress towards the next,
+/// shown just above the health bar.
+pub struct ExperienceBar {
+	label:   Sprite,
+	frame:   Sprite,
+	fill:    Sprite,
+	max:     Sprite,
+	number:  NumberSprite,
+
+	shown: usize, // value of `number`
+}
+
+impl ExperienceBar {
+	pub fn new(graphics: &mut graphics::Graphics) -> ExperienceBar {
+		let path = format!("assets/base/TextBox.bmp");
+		let size = (EXPERIENCE_BAR_W, EXPERIENCE_BAR_H);
+
+		ExperienceBar {
+			label: Sprite::new(graphics, (LEVEL_LABEL_OFS_X, LEVEL_LABEL_OFS_Y),
+			                   (LEVEL_LABEL_W, EXPERIENCE_BAR_H), path.clone()),
+			frame: Sprite::new(graphics, (EXPERIENCE_BAR_OFS_X, EXPERIENCE_BAR_OFS_Y),
+			                   size, path.clone()),
+			fill:  Sprite::new(graphics, (EXPERIENCE_BAR_OFS_X, EXPERIENCE_FILL_OFS_Y),
+			                   size, path.clone()),
+			max:   Sprite::new(graphics, (EXPERIENCE_MAX_OFS_X, EXPERIENCE_BAR_OFS_Y),
+			                   size, path),
+			number: NumberSprite::new(graphics, 1),
+
+			shown: 1,
+		}
+	}
+
+	pub fn draw(&mut self, display: &mut graphics::Graphics, experience: &weapons::Experience) {
+		self.label.draw(display, (LEVEL_LABEL_X, LEVEL_Y));
+
+		// only rebuild the digits when they've changed
+		let level = experience.level();
+		if self.shown != level {
+			self.number = NumberSprite::new(display, level as i32);
+			self.shown  = level;
+		}
+
+		self.number.draw(display, (LEVEL_NUMBER_X, LEVEL_Y));
+
+		if experience.is_max() {
+			self.max.draw(display, (EXPERIENCE_BAR_X, LEVEL_Y));
+			return;
+		}
+
+		let (points, needed) = experience.progress();
+		self.frame.draw(display, (EXPERIENCE_BAR_X, LEVEL_Y));
+		if points > 0 {
+			let ratio = points as f64 / needed as f64;
+			self.fill.set_width(EXPERIENCE_BAR_W.to_game() * units::Game(ratio));
+			self.fill.draw(display, (EXPERIENCE_BAR_X, LEVEL_Y));
+		}
+	}
+}
+
+/// The current weapon's icon & its remaining ammunition, shown
+/// above its level.
+///
+/// Weapons which never run out show no ammunition at all.
+pub struct WeaponStatus {
+	icon:     Sprite,
+	ammo:     NumberSprite,
+	max_ammo: NumberSprite,
+
+	shown: (usize, Option<(i32, i32)>), // arms index & ammo drawn by the sprites
+}
+
+impl WeaponStatus {
+	pub fn new(graphics: &mut graphics::Graphics) -> WeaponStatus {
+		WeaponStatus {
+			icon:     weapon_icon(graphics, 0),
+			ammo:     NumberSprite::new(graphics, 0),
+			max_ammo: NumberSprite::new(graphics, 0),
+
+			shown: (0, Some((0, 0))),
+		}
+	}
+
+	pub fn draw(&mut self, display: &mut graphics::Graphics, weapon: &weapons::Weapon) {
+		// only rebuild the sprites when they've changed
+		let (arms_index, ammo) = (weapon.arms_index(), weapon.ammo());
+		if self.shown.0 != arms_index {
+			self.icon = weapon_icon(display, arms_index);
+		}
+
+		if let Some((left, max)) = ammo {
+			if self.shown.1 != ammo {
+				self.ammo     = NumberSprite::new(display, left);
+				self.max_ammo = NumberSprite::new(display, max);
+			}
+		}
+
+		self.shown = (arms_index, ammo);
+		self.icon.draw(display, (WEAPON_ICON_X, WEAPON_ICON_Y));
+
+		if ammo.is_some() {
+			self.ammo.draw(display, (AMMO_X, AMMO_Y));
+			self.max_ammo.draw(display, (AMMO_X, MAX_AMMO_Y));
+		}
+	}
+}
+
+/// A "Yes / No" choice in the center of the screen, w/ a cursor
+/// pointing at the current selection.
+pub struct YesNoPrompt {
+	frame:   Sprite,
+	cursor:  Sprite,
+}
+
+impl YesNoPrompt {
+	pub fn new(graphics: &mut graphics::Graphics) -> YesNoPrompt {
+		let path = format!("assets/base/TextBox.bmp");
+
+		YesNoPrompt {
+			frame:  Sprite::new(graphics, (PROMPT_OFS_X, PROMPT_OFS_Y),
+			                    (PROMPT_W, PROMPT_H), path.clone()),
+			cursor: cursor(graphics),
+		}
+	}
+
+	/// Draws the prompt w/ the cursor beside "Yes" if `yes`, otherwise "No".
+	pub fn draw(&mut self, display: &mut graphics::Graphics, yes: bool) {
+		let x = (game::SCREEN_WIDTH.to_game() - PROMPT_W) / units::Game(2.0);
+		let y = (game::SCREEN_HEIGHT.to_game() - PROMPT_H) / units::Game(2.0);
+		self.frame.draw(display, (x, y));
+
+		let cursor_x = if yes { CURSOR_YES_X } else { CURSOR_NO_X };
+		self.cursor.draw(display, (x + cursor_x, y + CURSOR_Y));
+	}
+}
+
+/// The hand which points at the current selection in menus & prompts.
+pub fn cursor(graphics: &mut graphics::Graphics) -> Sprite {
+	Sprite::new(graphics, (CURSOR_OFS_X, CURSOR_OFS_Y),
+	            (units::Tile(1), units::Tile(1)), format!("assets/base/TextBox.bmp"))
+}
+
+/// The icon of the weapon found at `arms_index` on the arms sheets.
+pub fn weapon_icon(graphics: &mut graphics::Graphics, arms_index: usize) -> Sprite {
+	Sprite::new(graphics, (units::Tile(arms_index), units::Tile(0)),
+	            (units::Tile(1), units::Tile(1)), format!("assets/base/ArmsImage.bmp"))
+}
+
+/// Width of the fill at full health.
+fn full_width() -> units::Game {
+	HEALTH_FILL_W.to_game() - FILL_SHIFT
+}
+
+#[test]
+fn test_damage_segment_drains() {
+	let mut display = graphics::Graphics::headless();
+	let mut bar = HealthBar::new(&mut display, 4, 4);
+	let full = bar.damage_width();
+
+	bar.set_health(2);
+	bar.update(units::Millis(400));
+	assert!(bar.damage_width() == full);
+
+	// drains after the delay, but never past the remaining health
+	bar.update(units::Millis(200));
+	assert!(bar.damage_width() < full);
+	for _ in 0..100 {
+		bar.update(units::Millis(16));
+	}
+	assert!(bar.damage_width() == full / units::Game(2.0));
+}
@@ -1,17 +1,46 @@
+use sdl2::controller::{Axis, Button};
 use sdl2::keyboard;
 
 use std::collections::HashMap;
+use std::fs::File;
+use std::io::{Read, Write};
+use std::path::Path;
 
 /// Responds to inquiries regarding three sets of keyboard input.
 ///
 ///- Pressed keys
 ///- Released keys
 ///- Held keys
+///
+/// The same three sets are kept for game controller buttons. Tilting the
+/// left stick beyond the dead zone is reported as the matching D-pad button,
+/// though the stick keeps sets of its own: so that letting go of one does
+/// not release a button still held by the other.
+///
+/// The stream of key events can optionally be recorded, or replaced
+/// entirely by a previously made recording.
+///
+/// The same inquiries can be made of an `Action`, in which case any of
+/// the keys or buttons bound to it will do.
 pub struct Input {
 	pressed_keys:   HashMap<u32, bool>,
 	released_keys:  HashMap<u32, bool>,
 	held_keys:      HashMap<u32, bool>,
 
+	pressed_buttons:   HashMap<u32, bool>,
+	released_buttons:  HashMap<u32, bool>,
+	held_buttons:      HashMap<u32, bool>,
+
+	// D-pad buttons pressed, released & held by the left stick
+	pressed_stick:     HashMap<u32, bool>,
+	released_stick:    HashMap<u32, bool>,
+	held_stick:        HashMap<u32, bool>,
+
+	bindings:   Bindings,
+
+	frame:      u64,
+	recording:  Option<Recording>,
+	playback:   Option<Playback>,
 }
 
 impl Input {
@@ -20,25 +49,81 @@ impl Input {
 			pressed_keys:   HashMap::<u32, bool>::new(),
 			released_keys:  HashMap::<u32, bool>::new(),
 			held_keys:      HashMap::<u32, bool>::new(),
+
+			pressed_buttons:   HashMap::<u32, bool>::new(),
+			released_buttons:  HashMap::<u32, bool>::new(),
+			held_buttons:      HashMap::<u32, bool>::new(),
+
+			pressed_stick:     HashMap::<u32, bool>::new(),
+			released_stick:    HashMap::<u32, bool>::new(),
+			held_stick:        HashMap::<u32, bool>::new(),
+
+			bindings:   Bindings::defaults(),
+
+			frame:      0,
+			recording:  None,
+			playback:   None,
 		}
 	}
 
-	/// Resets the toggle states of pressed & released keys.
+	/// Resets the toggle states of pressed & released keys and buttons.
+	/// If a recording is being played back: the events of the next frame
+	/// are applied immediately.
 	pub fn begin_new_frame(&mut self) {
 		self.pressed_keys.clear();
 		self.released_keys.clear();
+		self.pressed_buttons.clear();
+		self.released_buttons.clear();
+		self.pressed_stick.clear();
+		self.released_stick.clear();
+
+		self.frame += 1;
+		self.play_frame();
 	}
 
 	/// Handles a key down event
+	/// (Ignored while a recording is being played back.)
 	pub fn key_down_event(&mut self, key: keyboard::Keycode) {
-		self.pressed_keys.insert(key as u32, true);
-		self.held_keys.insert(key as u32, true);
+		if self.playback.is_some() { return; }
+		self.apply(KeyEvent::Down(key));
 	}
 
 	/// Handles a key up event
+	/// (Ignored while a recording is being played back.)
 	pub fn key_up_event(&mut self, key: keyboard::Keycode) {
-		self.released_keys.insert(key as u32, true);
-		self.held_keys.insert(key as u32, false);
+		if self.playback.is_some() { return; }
+		self.apply(KeyEvent::Up(key));
+	}
+
+	/// Handles a controller button down event
+	/// (Ignored while a recording is being played back.)
+	pub fn button_down_event(&mut self, button: Button) {
+		if self.playback.is_some() { return; }
+		self.apply(KeyEvent::ButtonDown(button));
+	}
+
+	/// Handles a controller button up event
+	/// (Ignored while a recording is being played back.)
+	pub fn button_up_event(&mut self, button: Button) {
+		if self.playback.is_some() { return; }
+		self.apply(KeyEvent::ButtonUp(button));
+	}
+
+	/// Handles the motion of an analog stick: the left stick presses &
+	/// releases the D-pad as it crosses the dead zone, other axes are ignored.
+	/// (Ignored while a recording is being played back.)
+	pub fn axis_event(&mut self, axis: Axis, value: i16) {
+		if self.playback.is_some() { return; }
+
+		let (negative, positive) = match axis {
+			Axis::LeftX => (Button::DPadLeft, Button::DPadRight),
+			Axis::LeftY => (Button::DPadUp, Button::DPadDown),
+			_ => return,
+		};
+
+		let (value, dead_zone) = (value as i32, self.bindings.dead_zone() as i32);
+		self.tilt_stick(negative, value < -dead_zone);
+		self.tilt_stick(positive, value > dead_zone);
 	}
 
 	/// Responds true if key was pressed since last call to `beginNewFrame()`.
@@ -50,7 +135,7 @@ impl Input {
 			None             => false,
 		}
 	}
-	
+
 	/// Responds true if key was released since last call to `beginNewFrame()`.
 	/// Responds false otherwise.
 	pub fn was_key_released(&self, key: keyboard::Keycode) -> bool {
@@ -60,7 +145,7 @@ impl Input {
 			None             => false,
 		}
 	}
-	
+
 	/// Responds true if key has been pressed since last call to `beginNewFrame()`
 	/// but _has not yet been released._
 	///
@@ -72,4 +157,546 @@ impl Input {
 			None             => false,
 		}
 	}
+
+	pub fn was_button_pressed(&self, button: Button) -> bool {
+		self.pressed_buttons.get(&(button as u32)).cloned().unwrap_or(false)
+		|| self.pressed_stick.get(&(button as u32)).cloned().unwrap_or(false)
+	}
+
+	pub fn was_button_released(&self, button: Button) -> bool {
+		self.released_buttons.get(&(button as u32)).cloned().unwrap_or(false)
+		|| self.released_stick.get(&(button as u32)).cloned().unwrap_or(false)
+	}
+
+	pub fn is_button_held(&self, button: Button) -> bool {
+		self.held_buttons.get(&(button as u32)).cloned().unwrap_or(false)
+		|| self.held_stick.get(&(button as u32)).cloned().unwrap_or(false)
+	}
+
+	/// Responds true if any key or button bound to `action` was pressed
+	/// since last call to `beginNewFrame()`.
+	pub fn was_pressed(&self, action: Action) -> bool {
+		self.bindings.keys(action).iter().any(|&key| self.was_key_pressed(key))
+		|| self.bindings.buttons(action).iter().any(|&button| self.was_button_pressed(button))
+	}
+
+	/// Responds true if any key or button bound to `action` was released
+	/// since last call to `beginNewFrame()`.
+	pub fn was_released(&self, action: Action) -> bool {
+		self.bindings.keys(action).iter().any(|&key| self.was_key_released(key))
+		|| self.bindings.buttons(action).iter().any(|&button| self.was_button_released(button))
+	}
+
+	/// Responds true if any key or button bound to `action` is being held.
+	pub fn is_held(&self, action: Action) -> bool {
+		self.bindings.keys(action).iter().any(|&key| self.is_key_held(key))
+		|| self.bindings.buttons(action).iter().any(|&button| self.is_button_held(button))
+	}
+
+	pub fn set_bindings(&mut self, bindings: Bindings) {
+		self.bindings = bindings;
+	}
+
+	/// Starts logging every key event, stamped w/ the frame it arrived on.
+	/// (Frames are counted from this call.)
+	pub fn start_recording(&mut self) {
+		self.frame     = 0;
+		self.recording = Some(Recording::new());
+	}
+
+	/// Stops logging key events and hands back everything logged so far.
+	pub fn stop_recording(&mut self) -> Option<Recording> {
+		self.recording.take()
+	}
+
+	/// Replaces the live key events w/ those from `recording`.
+	///
+	/// Frames are counted from this call, each `begin_new_frame()` advances
+	/// the recording by one frame. Once the recording has been exhausted
+	/// live input is accepted again.
+	pub fn start_playback(&mut self, recording: Recording) {
+		self.frame    = 0;
+		self.playback = Some(Playback { recording: recording, cursor: 0 });
+		self.play_frame();
+	}
+
+	pub fn is_playing_back(&self) -> bool {
+		self.playback.is_some()
+	}
+
+	fn apply(&mut self, event: KeyEvent) {
+		match event {
+			KeyEvent::Down(key) => {
+				self.pressed_keys.insert(key as u32, true);
+				self.held_keys.insert(key as u32, true);
+			},
+
+			KeyEvent::Up(key) => {
+				self.released_keys.insert(key as u32, true);
+				self.held_keys.insert(key as u32, false);
+			},
+
+			KeyEvent::ButtonDown(button) => {
+				self.pressed_buttons.insert(button as u32, true);
+				self.held_buttons.insert(button as u32, true);
+			},
+
+			KeyEvent::ButtonUp(button) => {
+				self.released_buttons.insert(button as u32, true);
+				self.held_buttons.insert(button as u32, false);
+			},
+
+			KeyEvent::StickDown(button) => {
+				self.pressed_stick.insert(button as u32, true);
+				self.held_stick.insert(button as u32, true);
+			},
+
+			KeyEvent::StickUp(button) => {
+				self.released_stick.insert(button as u32, true);
+				self.held_stick.insert(button as u32, false);
+			},
+		}
+
+		match self.recording {
+			Some(ref mut recording) => recording.push(self.frame, event),
+			None => {},
+		}
+	}
+
+	/// Presses or releases `button` on behalf of the stick,
+	/// if it is not already in that state.
+	fn tilt_stick(&mut self, button: Button, tilted: bool) {
+		let was_tilted = self.held_stick.get(&(button as u32)).cloned().unwrap_or(false);
+		if tilted == was_tilted { return; }
+
+		self.apply(if tilted { KeyEvent::StickDown(button) } else { KeyEvent::StickUp(button) });
+	}
+
+	/// Applies the recorded events belonging to the current frame.
+	fn play_frame(&mut self) {
+		let events = match self.playback {
+			Some(ref mut playback) => playback.events_until(self.frame),
+			None => return,
+		};
+
+		for event in events.into_iter() {
+			self.apply(event);
+		}
+
+		if self.playback.as_ref().map_or(false, |playback| playback.is_finished()) {
+			self.playback = None;
+		}
+	}
+}
+
+/// Something the player can do, regardless of which keys they do it with.
+#[derive(Clone,Copy,PartialEq,Eq,Hash)]
+pub enum Action {
+	MoveLeft,
+	MoveRight,
+	LookUp,
+	LookDown,
+	Jump,
+	Fire,
+	NextWeapon,
+	PreviousWeapon,
+	Inventory,
+	Pause,
+	/// Advances the paused world by a single tick.
+	Step,
+}
+
+impl Action {
+	/// Looks up an action by the name it is given in a controls file.
+	pub fn from_name(name: &str) -> Option<Action> {
+		match name {
+			"move_left"  => Some(Action::MoveLeft),
+			"move_right" => Some(Action::MoveRight),
+			"look_up"    => Some(Action::LookUp),
+			"look_down"  => Some(Action::LookDown),
+			"jump"       => Some(Action::Jump),
+			"fire"       => Some(Action::Fire),
+			"next_weapon"     => Some(Action::NextWeapon),
+			"previous_weapon" => Some(Action::PreviousWeapon),
+			"inventory"  => Some(Action::Inventory),
+			"pause"      => Some(Action::Pause),
+			"step"       => Some(Action::Step),
+			_ => None,
+		}
+	}
+}
+
+/// The keys & controller buttons bound to each `Action`; an action may
+/// have any number of either.
+///
+/// Bindings are stored as plain text, one action per line:
+/// `<action> <key|pad:button> [...]` where keys & buttons are given by their
+/// SDL names, e.g: `jump Z Space pad:a`. Each line replaces all of the
+/// action's default bindings, actions which are not mentioned keep theirs.
+///
+/// The stick's dead zone (0 - 32767) is set by a line `dead_zone <value>`.
+/// Blank lines & lines starting w/ `#` are ignored.
+#[derive(Clone)]
+pub struct Bindings {
+	keys:       HashMap<Action, Vec<keyboard::Keycode>>,
+	buttons:    HashMap<Action, Vec<Button>>,
+	dead_zone:  i16,
+}
+
+impl Bindings {
+	/// The original game's controls.
+	pub fn defaults() -> Bindings {
+		use sdl2::keyboard::Keycode;
+
+		let mut bindings = Bindings {
+			keys:       HashMap::new(),
+			buttons:    HashMap::new(),
+			dead_zone:  DEFAULT_DEAD_ZONE,
+		};
+
+		bindings.bind(Action::MoveLeft,  Keycode::Left);
+		bindings.bind(Action::MoveRight, Keycode::Right);
+		bindings.bind(Action::LookUp,    Keycode::Up);
+		bindings.bind(Action::LookDown,  Keycode::Down);
+		bindings.bind(Action::Jump,      Keycode::Z);
+		bindings.bind(Action::Fire,      Keycode::X);
+		bindings.bind(Action::NextWeapon,     Keycode::S);
+		bindings.bind(Action::PreviousWeapon, Keycode::A);
+		bindings.bind(Action::Inventory, Keycode::Q);
+		bindings.bind(Action::Pause,     Keycode::P);
+		bindings.bind(Action::Step,      Keycode::Period);
+
+		bindings.bind_button(Action::MoveLeft,  Button::DPadLeft);
+		bindings.bind_button(Action::MoveRight, Button::DPadRight);
+		bindings.bind_button(Action::LookUp,    Button::DPadUp);
+		bindings.bind_button(Action::LookDown,  Button::DPadDown);
+		bindings.bind_button(Action::Jump,      Button::A);
+		bindings.bind_button(Action::Fire,      Button::X);
+		bindings.bind_button(Action::NextWeapon,     Button::RightShoulder);
+		bindings.bind_button(Action::PreviousWeapon, Button::LeftShoulder);
+		bindings.bind_button(Action::Inventory, Button::Y);
+		bindings.bind_button(Action::Pause,     Button::Start);
+		bindings.bind_button(Action::Step,      Button::Back);
+		bindings
+	}
+
+	/// Adds `key` to the keys which trigger `action`.
+	pub fn bind(&mut self, action: Action, key: keyboard::Keycode) {
+		self.keys.entry(action).or_insert(Vec::new()).push(key);
+	}
+
+	/// Adds `button` to the controller buttons which trigger `action`.
+	pub fn bind_button(&mut self, action: Action, button: Button) {
+		self.buttons.entry(action).or_insert(Vec::new()).push(button);
+	}
+
+	pub fn keys(&self, action: Action) -> &[keyboard::Keycode] {
+		match self.keys.get(&action) {
+			Some(keys) => &keys[..],
+			None       => &[],
+		}
+	}
+
+	pub fn buttons(&self, action: Action) -> &[Button] {
+		match self.buttons.get(&action) {
+			Some(buttons) => &buttons[..],
+			None          => &[],
+		}
+	}
+
+	/// How far the stick must be tilted before it counts as a press.
+	pub fn dead_zone(&self) -> i16 {
+		self.dead_zone
+	}
+
+	pub fn load(path: &Path) -> Result<Bindings, String> {
+		let mut text = String::new();
+		let mut file = try!(File::open(path).map_err(|err| {
+			format!("could not open controls {}: {}", path.display(), err)
+		}));
+
+		try!(file.read_to_string(&mut text).map_err(|err| {
+			format!("could not read controls {}: {}", path.display(), err)
+		}));
+
+		Bindings::parse(&text[..])
+	}
+
+	pub fn parse(text: &str) -> Result<Bindings, String> {
+		let mut bindings = Bindings::defaults();
+
+		for (idx, line) in text.lines().enumerate() {
+			let line = line.trim();
+			if line.is_empty() || line.starts_with("#") { continue; }
+
+			let fields: Vec<&str> = line.split_whitespace().collect();
+			if fields.len() < 2 {
+				return Err(format!("line {}: expected `<action> <key|pad:button> [...]`", idx + 1));
+			}
+
+			if fields[0] == "dead_zone" {
+				bindings.dead_zone = try!(fields[1].parse::<i16>().ok()
+				                                   .and_then(|zone| if zone >= 0 { Some(zone) } else { None })
+				                                   .ok_or(format!("line {}: invalid dead zone `{}`", idx + 1, fields[1])));
+				continue;
+			}
+
+			let action = try!(Action::from_name(fields[0]).ok_or(
+				format!("line {}: unknown action `{}`", idx + 1, fields[0])
+			));
+
+			let (mut keys, mut buttons) = (Vec::new(), Vec::new());
+			for name in fields[1..].iter() {
+				if name.starts_with("pad:") {
+					buttons.push(try!(Button::from_string(&name[4..]).ok_or(
+						format!("line {}: unknown button `{}`", idx + 1, &name[4..])
+					)));
+				} else {
+					keys.push(try!(keyboard::Keycode::from_name(name).ok_or(
+						format!("line {}: unknown key `{}`", idx + 1, name)
+					)));
+				}
+			}
+
+			bindings.keys.insert(action, keys);
+			bindings.buttons.insert(action, buttons);
+		}
+
+		Ok(bindings)
+	}
+}
+
+/// The default dead zone of the analog sticks, roughly a quarter of their range.
+pub const DEFAULT_DEAD_ZONE: i16 = 8000;
+
+/// A single change in the state of a key or controller button.
+/// The left stick presses & releases D-pad buttons of its own.
+#[derive(Clone,Copy,PartialEq,Eq)]
+pub enum KeyEvent {
+	Down(keyboard::Keycode),
+	Up(keyboard::Keycode),
+	ButtonDown(Button),
+	ButtonUp(Button),
+	StickDown(Button),
+	StickUp(Button),
+}
+
+/// A log of key events, each stamped w/ the frame they were observed on.
+///
+/// Recordings are stored as plain text, one event per line:
+/// `<frame> <down|up> <keycode>` for keys, or
+/// `<frame> <button_down|button_up> <button>` for controller buttons, or
+/// `<frame> <stick_down|stick_up> <button>` for D-pad buttons tilted by the stick.
+/// Blank lines & lines starting w/ `#` are ignored.
+#[derive(Clone)]
+pub struct Recording {
+	events: Vec<(u64, KeyEvent)>,
+}
+
+impl Recording {
+	pub fn new() -> Recording {
+		Recording { events: Vec::new() }
+	}
+
+	pub fn push(&mut self, frame: u64, event: KeyEvent) {
+		self.events.push((frame, event));
+	}
+
+	pub fn events(&self) -> &[(u64, KeyEvent)] {
+		&self.events[..]
+	}
+
+	/// Reads a recording previously written by `save()`.
+	pub fn load(path: &Path) -> Result<Recording, String> {
+		let mut text = String::new();
+		let mut file = try!(File::open(path).map_err(|err| {
+			format!("could not open recording {}: {}", path.display(), err)
+		}));
+
+		try!(file.read_to_string(&mut text).map_err(|err| {
+			format!("could not read recording {}: {}", path.display(), err)
+		}));
+
+		Recording::parse(&text[..])
+	}
+
+	pub fn save(&self, path: &Path) -> Result<(), String> {
+		let mut file = try!(File::create(path).map_err(|err| {
+			format!("could not create recording {}: {}", path.display(), err)
+		}));
+
+		file.write_all(self.to_text().as_bytes()).map_err(|err| {
+			format!("could not write recording {}: {}", path.display(), err)
+		})
+	}
+
+	pub fn parse(text: &str) -> Result<Recording, String> {
+		let mut recording = Recording::new();
+
+		for (idx, line) in text.lines().enumerate() {
+			let line = line.trim();
+			if line.is_empty() || line.starts_with("#") { continue; }
+
+			let fields: Vec<&str> = line.split_whitespace().collect();
+			if fields.len() != 3 {
+				return Err(format!("line {}: expected `<frame> <down|up> <keycode>`", idx + 1));
+			}
+
+			let frame = try!(fields[0].parse::<u64>().map_err(|_| {
+				format!("line {}: invalid frame `{}`", idx + 1, fields[0])
+			}));
+
+			let event = match fields[1] {
+				"down" => KeyEvent::Down(try!(parse_keycode(idx, fields[2]))),
+				"up"   => KeyEvent::Up(try!(parse_keycode(idx, fields[2]))),
+				"button_down" => KeyEvent::ButtonDown(try!(parse_button(idx, fields[2]))),
+				"button_up"   => KeyEvent::ButtonUp(try!(parse_button(idx, fields[2]))),
+				"stick_down"  => KeyEvent::StickDown(try!(parse_button(idx, fields[2]))),
+				"stick_up"    => KeyEvent::StickUp(try!(parse_button(idx, fields[2]))),
+				other  => return Err(format!("line {}: unknown event `{}`", idx + 1, other)),
+			};
+
+			recording.push(frame, event);
+		}
+
+		Ok(recording)
+	}
+
+	pub fn to_text(&self) -> String {
+		let mut text = String::new();
+		for &(frame, event) in self.events.iter() {
+			let (direction, control) = match event {
+				KeyEvent::Down(key) => ("down", format!("{}", key as i32)),
+				KeyEvent::Up(key)   => ("up", format!("{}", key as i32)),
+				KeyEvent::ButtonDown(button) => ("button_down", button.string()),
+				KeyEvent::ButtonUp(button)   => ("button_up", button.string()),
+				KeyEvent::StickDown(button)  => ("stick_down", button.string()),
+				KeyEvent::StickUp(button)    => ("stick_up", button.string()),
+			};
+
+			text.push_str(&format!("{} {} {}\n", frame, direction, control)[..]);
+		}
+
+		text
+	}
+}
+
+fn parse_keycode(idx: usize, field: &str) -> Result<keyboard::Keycode, String> {
+	field.parse::<i32>().ok()
+	     .and_then(keyboard::Keycode::from_i32)
+	     .ok_or(format!("line {}: unknown keycode `{}`", idx + 1, field))
+}
+
+fn parse_button(idx: usize, field: &str) -> Result<Button, String> {
+	Button::from_string(field).ok_or(format!("line {}: unknown button `{}`", idx + 1, field))
+}
+
+/// Walks a recording, frame by frame.
+struct Playback {
+	recording:  Recording,
+	cursor:     usize,
+}
+
+impl Playback {
+	/// Yields all events which have not yet been played, up to & incl. `frame`
+	fn events_until(&mut self, frame: u64) -> Vec<KeyEvent> {
+		let mut events = Vec::new();
+		while self.cursor < self.recording.events.len() {
+			let (at, event) = self.recording.events[self.cursor];
+			if at > frame { break; }
+
+			events.push(event);
+			self.cursor += 1;
+		}
+
+		events
+	}
+
+	fn is_finished(&self) -> bool {
+		self.cursor >= self.recording.events.len()
+	}
+}
+
+#[test]
+fn test_recording_round_trip() {
+	let mut recording = Recording::new();
+	recording.push(0, KeyEvent::Down(keyboard::Keycode::Right));
+	recording.push(4, KeyEvent::Down(keyboard::Keycode::Z));
+	recording.push(9, KeyEvent::Up(keyboard::Keycode::Z));
+	recording.push(9, KeyEvent::ButtonDown(Button::A));
+	recording.push(12, KeyEvent::StickDown(Button::DPadLeft));
+
+	let parsed = Recording::parse(&recording.to_text()[..]).unwrap();
+	assert!(parsed.events() == recording.events());
+	assert!(Recording::parse("3 sideways 27").is_err());
+}
+
+#[test]
+fn test_playback_replaces_live_input() {
+	let mut recording = Recording::new();
+	recording.push(1, KeyEvent::Down(keyboard::Keycode::Z));
+	recording.push(2, KeyEvent::Up(keyboard::Keycode::Z));
+
+	let mut input = Input::new();
+	input.start_playback(recording);
+	input.key_down_event(keyboard::Keycode::Left);
+	assert!(!input.is_key_held(keyboard::Keycode::Left));
+
+	input.begin_new_frame();
+	assert!(input.was_key_pressed(keyboard::Keycode::Z));
+
+	input.begin_new_frame();
+	assert!(input.was_key_released(keyboard::Keycode::Z));
+	assert!(!input.is_playing_back());
+}
+
+#[test]
+fn test_bindings_remap_actions() {
+	let bindings = Bindings::parse("# comment\njump Space Return\n").unwrap();
+	assert!(bindings.keys(Action::Jump) == [keyboard::Keycode::Space, keyboard::Keycode::Return]);
+	assert!(bindings.keys(Action::Fire) == [keyboard::Keycode::X]);
+	assert!(Bindings::parse("dance Z").is_err());
+
+	let mut input = Input::new();
+	input.set_bindings(bindings);
+	input.key_down_event(keyboard::Keycode::Return);
+	assert!(input.was_pressed(Action::Jump) && input.is_held(Action::Jump));
+
+	input.key_down_event(keyboard::Keycode::Z);
+	input.begin_new_frame();
+	assert!(!input.was_pressed(Action::Jump));
+}
+
+#[test]
+fn test_stick_presses_dpad() {
+	let mut input = Input::new();
+	input.axis_event(Axis::LeftX, -(DEFAULT_DEAD_ZONE / 2));
+	assert!(!input.is_held(Action::MoveLeft));
+
+	input.axis_event(Axis::LeftX, -32768);
+	assert!(input.was_pressed(Action::MoveLeft) && input.is_held(Action::MoveLeft));
+
+	// holding the stick still is not a fresh press
+	input.begin_new_frame();
+	input.axis_event(Axis::LeftX, -20000);
+	assert!(input.is_held(Action::MoveLeft) && !input.was_pressed(Action::MoveLeft));
+
+	input.axis_event(Axis::LeftX, 20000);
+	assert!(input.was_released(Action::MoveLeft) && input.is_held(Action::MoveRight));
+}
+
+#[test]
+fn test_stick_does_not_release_held_dpad() {
+	let mut input = Input::new();
+	input.button_down_event(Button::DPadRight);
+	input.axis_event(Axis::LeftX, 20000);
+	input.begin_new_frame();
+
+	// letting go of the stick leaves the D-pad held, and vice versa
+	input.axis_event(Axis::LeftX, 0);
+	assert!(input.is_held(Action::MoveRight));
+
+	input.axis_event(Axis::LeftX, 20000);
+	input.button_up_event(Button::DPadRight);
+	assert!(input.is_held(Action::MoveRight));
 }
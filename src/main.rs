@@ -1,23 +1,98 @@
 extern crate num;
 extern crate sdl2;
 
+use std::env;
+use std::path::Path;
+
 pub mod backdrop;
+pub mod camera;
 pub mod collisions;
 pub mod enemies;
 pub mod game;
 pub mod graphics;
+pub mod hud;
 pub mod input;
 pub mod map;
 pub mod number_sprite;
+pub mod particles;
 pub mod player;
+pub mod scenes;
 pub mod sprite;
 pub mod units;
+pub mod weapons;
 
+/// Usage: `rust-story [--map <file> | --stage <pxm> <pxa>] [--controls <file>]
+///                    [--record <file>] [--replay <file>]`
+///
+/// `--map` starts the game on the map file `<file>` rather than the demo map.
+/// `--stage` starts the game on an original Cave Story stage.
+/// `--controls` rebinds keys as described by `<file>`, see `input::Bindings`.
+/// `--record` logs every key event to `<file>` once the game exits.
+/// `--replay` plays back a previous log in place of the keyboard.
 pub fn main() {
+	let (mut map_path, mut record_path, mut replay_path) = (None, None, None);
+	let (mut stage_paths, mut controls_path) = (None, None);
+	let mut args = env::args().skip(1);
+	while let Some(arg) = args.next() {
+		match &arg[..] {
+			"--map"    => map_path    = args.next(),
+			"--stage"  => stage_paths = args.next().and_then(|pxm| {
+				args.next().map(|pxa| (pxm, pxa))
+			}),
+			"--controls" => controls_path = args.next(),
+			"--record" => record_path = args.next(),
+			"--replay" => replay_path = args.next(),
+			_ => panic!("unrecognized argument: {}", arg),
+		}
+	}
+
 	println!("initalizing sdl ...");
 	let sdl_context = sdl2::init().unwrap();
 
 	println!("let me tell you a story ...");
 	let mut story = ::game::Game::new(&sdl_context);
-	story.start();
+
+	if let Some(ref path) = map_path {
+		match story.load_map(Path::new(path)) {
+			Ok(_)    => {},
+			Err(msg) => panic!("could not load map: {}", msg),
+		}
+	}
+
+	if let Some((ref pxm, ref pxa)) = stage_paths {
+		match story.load_stage(Path::new(pxm), Path::new(pxa)) {
+			Ok(_)    => {},
+			Err(msg) => panic!("could not load stage: {}", msg),
+		}
+	}
+
+	if let Some(ref path) = controls_path {
+		match ::input::Bindings::load(Path::new(path)) {
+			Ok(bindings) => story.controller().set_bindings(bindings),
+			Err(msg)     => panic!("could not load controls: {}", msg),
+		}
+	}
+
+	if let Some(ref path) = replay_path {
+		match ::input::Recording::load(Path::new(path)) {
+			Ok(recording) => story.controller().start_playback(recording),
+			Err(msg)      => panic!("could not load recording {}: {}", path, msg),
+		}
+	}
+
+	if record_path.is_some() {
+		story.controller().start_recording();
+	}
+
+	if let Err(msg) = story.start() {
+		panic!("could not run the game: {}", msg);
+	}
+
+	if let Some(ref path) = record_path {
+		let recording = story.controller().stop_recording().unwrap();
+		match recording.save(Path::new(path)) {
+			Ok(_)    => println!("recorded input to {}", path),
+			Err(msg) => println!("{}", msg),
+		}
+	}
 }
@@ -0,0 +1,416 @@
+use std::collections::HashMap;
+
+use backdrop::Layer;
+use map::{Slope, TileType};
+use units;
+
+/// The contents of a map file, before any of its sprites are loaded.
+///
+/// Map files are plain text. A header of `key value` directives is
+/// followed by up to three layers, each introduced by a `[section]` line
+/// and consisting of exactly `height` rows:
+///
+/// ```text
+/// // comments run until the end of the line
+/// backdrop  assets/base/bkBlue.bmp
+/// tileset   assets/base/Stage/PrtCave.bmp
+/// size      20 15
+///
+/// [tiles]       // foreground: indices into the tileset, `.` for none
+/// [collision]   // one character per tile: `.` is air, `#` is a wall,
+///               // `-` is a platform which may be jumped through,
+///               // `^` are spikes, `~` is water,
+///               // `0` - `7` are slopes, see `map::Slope::from_index()`
+/// [sprites]     // background decorations, same format as `[tiles]`
+/// ```
+///
+/// Tileset indices count from the top-left of the sheet, left-to-right,
+/// `TILESET_COLUMNS` tiles per row.
+///
+/// A `backdrop` is fixed to the screen unless it is prefixed w/ a style:
+/// `backdrop parallax <fraction of camera speed> <path>` or
+/// `backdrop scroll <games per ms> <path>`. Several backdrops are stacked,
+/// the first one declared is painted furthest back.
+///
+/// Tiles are animated by declaring `animate <index> <frames> <ms per frame>`,
+/// optionally followed by a phase in ms per column & per row (e.g: so a
+/// water surface ripples from left to right rather than pulsing in unison.)
+/// The frames of an animated tile follow it left-to-right in the tileset.
+///
+/// `water <row>` floods the map from the top of that row downwards.
+///
+/// `start <col> <row>` is where the player first appears, while touching
+/// a `save <col> <row>` point makes it the place they respawn after dying.
+///
+/// `enemy <kind> <col> <row>` places an enemy, see `enemies::Kind` for
+/// the kinds there are. A map may declare any number of them.
+///
+/// Every row & column given must lie within the map's `size`.
+pub struct MapFile {
+	pub backdrops:  Vec<Layer>,
+	pub tileset:    String,
+	pub width:      usize,
+	pub height:     usize,
+	pub animations: HashMap<usize, TileAnimation>,
+	pub water_level: Option<usize>,
+	pub start:       Option<(usize, usize)>,
+	pub save_points: Vec<(usize, usize)>,
+	pub enemies:     Vec<(String, usize, usize)>,
+	/// Tiles which are under water whatever their collision, e.g: submerged
+	/// walls & spikes, as `(col, row)`. (Only stages have these.)
+	pub flooded:     Vec<(usize, usize)>,
+
+	pub tiles:      Vec<Vec<Option<usize>>>,
+	pub collision:  Vec<Vec<TileType>>,
+	pub sprites:    Vec<Vec<Option<usize>>>,
+}
+
+/// How a tile from the tileset is animated.
+#[derive(Clone,Copy)]
+pub struct TileAnimation {
+	pub num_frames:  units::Frame,
+	pub frame_time:  units::Millis,
+	pub phase_x:     units::Millis,
+	pub phase_y:     units::Millis,
+}
+
+/// Number of tiles in a single row of a stage's tileset.
+pub static TILESET_COLUMNS: usize = 16;
+
+#[derive(Clone,Copy,PartialEq)]
+enum Section { Header, Tiles, Collision, Sprites }
+
+/// Maps a character from the `[collision]` layer onto a `TileType`
+fn tile_type_from_char(symbol: char) -> Option<TileType> {
+	match symbol {
+		'.' => Some(TileType::Air),
+		'#' => Some(TileType::Wall),
+		'-' => Some(TileType::Platform),
+		'^' => Some(TileType::Spikes),
+		'~' => Some(TileType::Water),
+		'0'...'7' => Slope::from_index(symbol as u8 - b'0').map(TileType::Slope),
+		_   => None,
+	}
+}
+
+/// Parses the text of a map file.
+///
+/// Returns a message describing the first problem encountered,
+/// (incl. its line number) if the map is malformed.
+pub fn parse(text: &str) -> Result<MapFile, String> {
+	let (mut backdrops, mut tileset, mut size) = (Vec::new(), None, None);
+	let mut animations = HashMap::new();
+	let mut water_level = None;
+	let (mut start, mut save_points) = (None, Vec::new());
+	let mut enemies = Vec::new();
+	let mut positions = Vec::new(); // (line, col, row) of everything placed
+	let (mut tiles, mut collision, mut sprites) = (Vec::new(), Vec::new(), Vec::new());
+	let mut seen    = Vec::new();
+	let mut section = Section::Header;
+
+	for (idx, raw_line) in text.lines().enumerate() {
+		let line_no = idx + 1;
+		let line = match raw_line.find("//") {
+			Some(pos) => &raw_line[..pos],
+			None      => raw_line,
+		}.trim();
+
+		if line.is_empty() { continue; }
+
+		// switch layers
+		if line.starts_with("[") {
+			section = match line {
+				"[tiles]"     => Section::Tiles,
+				"[collision]" => Section::Collision,
+				"[sprites]"   => Section::Sprites,
+				_ => return Err(format!("line {}: unknown section `{}`", line_no, line)),
+			};
+
+			if size.is_none() {
+				return Err(format!("line {}: `size` must be declared before {}", line_no, line));
+			}
+
+			if seen.contains(&section) {
+				return Err(format!("line {}: {} declared more than once", line_no, line));
+			}
+
+			seen.push(section);
+			continue;
+		}
+
+		let (width, height) = size.unwrap_or((0, 0));
+		match section {
+			Section::Header => {
+				let mut fields = line.split_whitespace();
+				let key = fields.next().unwrap();
+				let values: Vec<&str> = fields.collect();
+
+				match (key, values.len()) {
+					("backdrop", _) => backdrops.push(try!(parse_backdrop(&values[..], line_no))),
+					("tileset", 1)  => tileset  = Some(values[0].to_string()),
+					("size", 2) => {
+						let w = try!(parse_number(values[0], line_no));
+						let h = try!(parse_number(values[1], line_no));
+						if w == 0 || h == 0 {
+							return Err(format!("line {}: map must be at least 1x1 tiles", line_no));
+						}
+
+						size = Some((w, h));
+					},
+
+					("animate", 3) | ("animate", 5) => {
+						let mut numbers = Vec::with_capacity(values.len());
+						for value in values.iter() {
+							numbers.push(try!(parse_number(value, line_no)));
+						}
+
+						if numbers[1] == 0 || numbers[2] == 0 {
+							return Err(format!("line {}: an animation needs at least one frame, \
+							                    lasting at least 1ms", line_no));
+						}
+
+						if animations.contains_key(&numbers[0]) {
+							return Err(format!("line {}: tile {} is already animated", line_no, numbers[0]));
+						}
+
+						animations.insert(numbers[0], TileAnimation {
+							num_frames: numbers[1] as units::Frame,
+							frame_time: units::Millis(numbers[2] as i64),
+							phase_x:    units::Millis(*numbers.get(3).unwrap_or(&0) as i64),
+							phase_y:    units::Millis(*numbers.get(4).unwrap_or(&0) as i64),
+						});
+					},
+
+					("water", 1) => {
+						let row = try!(parse_number(values[0], line_no));
+						positions.push((line_no, None, row));
+						water_level = Some(row);
+					},
+
+					("start", 2) => {
+						let (col, row) = try!(parse_position(&values[..], line_no));
+						positions.push((line_no, Some(col), row));
+						start = Some((col, row));
+					},
+
+					("save", 2)  => {
+						let (col, row) = try!(parse_position(&values[..], line_no));
+						positions.push((line_no, Some(col), row));
+						save_points.push((col, row));
+					},
+
+					("enemy", 3) => {
+						let (col, row) = try!(parse_position(&values[1..], line_no));
+						positions.push((line_no, Some(col), row));
+						enemies.push((values[0].to_string(), col, row));
+					},
+
+					("tileset", _) =>
+						return Err(format!("line {}: `tileset` expects a single path", line_no)),
+					("animate", _) =>
+						return Err(format!("line {}: expected `animate <index> <frames> <ms> [<phase x> <phase y>]`",
+						                   line_no)),
+					("size", _) =>
+						return Err(format!("line {}: `size` expects a width and height", line_no)),
+					("water", _) =>
+						return Err(format!("line {}: `water` expects the row at which the water begins", line_no)),
+					("start", _) | ("save", _) =>
+						return Err(format!("line {}: `{}` expects a column and row", line_no, key)),
+					("enemy", _) =>
+						return Err(format!("line {}: expected `enemy <kind> <col> <row>`", line_no)),
+					_ => return Err(format!("line {}: unknown directive `{}`", line_no, key)),
+				}
+			},
+
+			Section::Tiles | Section::Sprites => {
+				let row = try!(parse_tile_row(line, width, line_no));
+				let layer = if section == Section::Tiles { &mut tiles } else { &mut sprites };
+				if layer.len() == height {
+					return Err(format!("line {}: layer has more than {} rows", line_no, height));
+				}
+
+				layer.push(row);
+			},
+
+			Section::Collision => {
+				let mut row = Vec::with_capacity(width);
+				for symbol in line.chars().filter(|c| !c.is_whitespace()) {
+					match tile_type_from_char(symbol) {
+						Some(tile_type) => row.push(tile_type),
+						None => return Err(format!("line {}: unknown collision tile `{}`", line_no, symbol)),
+					}
+				}
+
+				if row.len() != width {
+					return Err(format!("line {}: expected {} tiles, found {}", line_no, width, row.len()));
+				}
+
+				if collision.len() == height {
+					return Err(format!("line {}: layer has more than {} rows", line_no, height));
+				}
+
+				collision.push(row);
+			},
+		}
+	}
+
+	let (width, height) = try!(size.ok_or(format!("map is missing its `size`")));
+	if backdrops.is_empty() { return Err(format!("map is missing its `backdrop`")); }
+	let tileset  = try!(tileset.ok_or(format!("map is missing its `tileset`")));
+
+	for &(line_no, col, row) in positions.iter() {
+		match col {
+			Some(col) if col >= width || row >= height =>
+				return Err(format!("line {}: {}, {} lies outside the {}x{} map",
+				                   line_no, col, row, width, height)),
+			None if row >= height =>
+				return Err(format!("line {}: row {} lies outside the {}x{} map",
+				                   line_no, row, width, height)),
+			_ => {},
+		}
+	}
+
+	// omitted layers are left empty
+	for &(name, rows) in [("[tiles]", tiles.len()),
+	                      ("[collision]", collision.len()),
+	                      ("[sprites]", sprites.len())].iter() {
+		if rows != 0 && rows != height {
+			return Err(format!("{} has {} rows, expected {}", name, rows, height));
+		}
+	}
+
+	if tiles.is_empty()     { tiles     = vec![vec![None; width]; height]; }
+	if sprites.is_empty()   { sprites   = vec![vec![None; width]; height]; }
+	if collision.is_empty() { collision = vec![vec![TileType::Air; width]; height]; }
+
+	Ok(MapFile {
+		backdrops: backdrops, tileset: tileset,
+		width: width, height: height,
+		animations: animations, water_level: water_level,
+		start: start, save_points: save_points, enemies: enemies,
+		flooded: Vec::new(),
+		tiles: tiles, collision: collision, sprites: sprites,
+	})
+}
+
+fn parse_position(values: &[&str], line_no: usize) -> Result<(usize, usize), String> {
+	let col = try!(parse_number(values[0], line_no));
+	let row = try!(parse_number(values[1], line_no));
+	Ok((col, row))
+}
+
+fn parse_backdrop(values: &[&str], line_no: usize) -> Result<Layer, String> {
+	let parse_speed = |field: &str| field.parse::<f64>().map_err(|_| {
+		format!("line {}: expected a speed, found `{}`", line_no, field)
+	});
+
+	match (values.len(), values.first().map(|style| *style)) {
+		(1, _)             => Ok(Layer::Fixed(values[0].to_string())),
+		(2, Some("fixed")) => Ok(Layer::Fixed(values[1].to_string())),
+		(3, Some("parallax")) => {
+			let speed = try!(parse_speed(values[1]));
+			Ok(Layer::Parallax(values[2].to_string(), speed))
+		},
+		(3, Some("scroll")) => {
+			let speed = try!(parse_speed(values[1]));
+			Ok(Layer::Scrolling(values[2].to_string(), units::Velocity(speed)))
+		},
+		_ => Err(format!("line {}: expected `backdrop [fixed | parallax <speed> | scroll <speed>] <path>`",
+		                 line_no)),
+	}
+}
+
+fn parse_number(field: &str, line_no: usize) -> Result<usize, String> {
+	field.parse::<usize>().map_err(|_| {
+		format!("line {}: expected a number, found `{}`", line_no, field)
+	})
+}
+
+fn parse_tile_row(line: &str, width: usize, line_no: usize) -> Result<Vec<Option<usize>>, String> {
+	let mut row = Vec::with_capacity(width);
+	for field in line.split_whitespace() {
+		row.push(match field {
+			"." => None,
+			_   => Some(try!(parse_number(field, line_no))),
+		});
+	}
+
+	if row.len() != width {
+		return Err(format!("line {}: expected {} tiles, found {}", line_no, width, row.len()));
+	}
+
+	Ok(row)
+}
+
+#[test]
+fn test_parse_map() {
+	let map = parse("
+		backdrop  bk.bmp
+		tileset   tiles.bmp
+		size      3 2
+		enemy     bat 2 0
+
+		[tiles]
+		.  .  .
+		1  1  1
+
+		[collision]
+		...
+		###
+	").unwrap();
+
+	assert_eq!((map.width, map.height), (3, 2));
+	assert_eq!(map.tiles[1][2], Some(1));
+	assert!(map.collision[1][0] == TileType::Wall);
+	assert!(map.collision[0][0] == TileType::Air);
+	assert_eq!(map.sprites[0][0], None);
+	assert!(map.enemies == vec![(format!("bat"), 2, 0)]);
+	assert!(map.backdrops == vec![Layer::Fixed(format!("bk.bmp"))]);
+}
+
+#[test]
+fn test_parse_backdrop_layers() {
+	let map = parse("
+		backdrop  bk.bmp
+		backdrop  parallax 0.5 hills.bmp
+		backdrop  scroll -0.05 clouds.bmp
+		tileset   tiles.bmp
+		size      1 1
+	").unwrap();
+
+	assert!(map.backdrops == vec![
+		Layer::Fixed(format!("bk.bmp")),
+		Layer::Parallax(format!("hills.bmp"), 0.5),
+		Layer::Scrolling(format!("clouds.bmp"), units::Velocity(-0.05)),
+	]);
+
+	assert!(parse("backdrop parallax fast bk.bmp\ntileset t.bmp\nsize 1 1").is_err());
+}
+
+#[test]
+fn test_parse_errors() {
+	let missing_size = parse("backdrop bk.bmp\ntileset tiles.bmp\n[tiles]\n1");
+	assert_eq!(missing_size.err().unwrap(), "line 3: `size` must be declared before [tiles]");
+
+	let short_row = parse("backdrop bk.bmp\ntileset t.bmp\nsize 2 1\n[collision]\n#");
+	assert_eq!(short_row.err().unwrap(), "line 5: expected 2 tiles, found 1");
+
+	let bad_tile = parse("size 1 1\n[collision]\n?");
+	assert_eq!(bad_tile.err().unwrap(), "line 3: unknown collision tile `?`");
+}
+
+#[test]
+fn test_parse_positions_outside_map() {
+	let header = "backdrop bk.bmp\ntileset t.bmp\n";
+
+	let start = parse(&format!("{}start 3 0\nsize 3 2", header));
+	assert_eq!(start.err().unwrap(), "line 3: 3, 0 lies outside the 3x2 map");
+
+	let enemy = parse(&format!("{}size 3 2\nenemy bat 0 2", header));
+	assert_eq!(enemy.err().unwrap(), "line 4: 0, 2 lies outside the 3x2 map");
+
+	let water = parse(&format!("{}size 3 2\nwater 5", header));
+	assert_eq!(water.err().unwrap(), "line 4: row 5 lies outside the 3x2 map");
+
+	assert!(parse(&format!("{}size 3 2\nsave 2 1\nwater 1", header)).is_ok());
+}
@@ -0,0 +1,715 @@
+use std::cell::RefCell;
+use std::cmp;
+use std::collections::hash_map::{HashMap, Entry};
+use std::fs::File;
+use std::io::Read;
+use std::iter::repeat;
+use std::ops::Range;
+use std::path::Path;
+use std::rc::Rc;
+
+use backdrop;
+use game;
+use graphics;
+use sprite;
+use units;
+
+use collisions::Rectangle;
+use units::{AsGame,AsTile};
+
+// Load map formats
+pub mod loader;
+pub mod stage;
+
+#[derive(Clone,Copy,PartialEq,Eq)]
+pub enum TileType {
+	Air,
+	Wall,
+	Slope(Slope),
+	/// Solid only to those landing on it from above, it may be jumped up
+	/// through, or dropped down through.
+	Platform,
+	/// Passable, but hurts anyone who touches it.
+	Spikes,
+	/// Passable, anyone inside it is submerged.
+	Water,
+}
+
+/// How much damage a player takes from touching spikes.
+pub static SPIKE_DAMAGE: units::HitPoints = 2;
+
+impl TileType {
+	/// How much damage a tile of this type deals to anyone touching it.
+	pub fn damage(&self) -> Option<units::HitPoints> {
+		match *self {
+			TileType::Spikes => Some(SPIKE_DAMAGE),
+			_                => None,
+		}
+	}
+}
+
+/// Which side of a sloped tile is solid.
+#[derive(Clone,Copy,PartialEq,Eq)]
+pub enum Surface {
+	/// Solid below the incline, it may be walked upon.
+	Floor,
+	/// Solid above the incline, it may be bumped into from below.
+	Ceiling,
+}
+
+/// A tile whose solid portion is bounded by an incline.
+///
+/// Like Cave Story, an incline rises by one tile over two tiles: the `tall`
+/// half of the pair is solid for at least half a tile, the other half is not.
+/// The solid portion grows thicker towards `thickest`.
+#[derive(Clone,Copy,PartialEq,Eq)]
+pub struct Slope {
+	pub surface:   Surface,
+	pub thickest:  sprite::Facing,
+	pub tall:      bool,
+}
+
+impl Slope {
+	/// The eight slopes, in the order of Cave Story's attributes (`0x50 - 0x57`)
+	pub fn from_index(index: u8) -> Option<Slope> {
+		let surface = if index < 4 { Surface::Ceiling } else { Surface::Floor };
+		let (thickest, tall) = match index % 4 {
+			0 => (sprite::Facing::West, true),
+			1 => (sprite::Facing::West, false),
+			2 => (sprite::Facing::East, false),
+			_ => (sprite::Facing::East, true),
+		};
+
+		if index < 8 {
+			Some(Slope { surface: surface, thickest: thickest, tall: tall })
+		} else { None }
+	}
+
+	/// How thick the solid portion of this tile is, `offset_x` units from its left edge.
+	pub fn thickness(&self, offset_x: units::Game) -> units::Game {
+		let units::Game(tile_size) = units::Tile(1).to_game();
+		let units::Game(offset_x)  = offset_x;
+		let fraction = (offset_x / tile_size).max(0.0).min(1.0);
+
+		let rise = match self.thickest {
+			sprite::Facing::West => 1.0 - fraction,
+			sprite::Facing::East => fraction,
+		};
+
+		let base = if self.tall { 1.0 } else { 0.0 };
+		units::Game(tile_size * (base + rise) / 2.0)
+	}
+
+	/// The y-coordinate of the incline at map x-coordinate `x`, for the slope at `row`, `col`
+	pub fn surface_at(&self, row: units::Tile, col: units::Tile, x: units::Game) -> units::Game {
+		let thickness = self.thickness(x - col.to_game());
+		match self.surface {
+			Surface::Floor   => (row + units::Tile(1)).to_game() - thickness,
+			Surface::Ceiling => row.to_game() + thickness,
+		}
+	}
+}
+
+#[derive(Clone,Copy)]
+pub struct CollisionTile {
+	pub tile_type:  TileType,
+	pub row:        units::Tile,
+	pub col:        units::Tile
+}
+
+impl CollisionTile {
+	pub fn new(row: units::Tile, col: units::Tile, 
+	           tile_type: TileType) -> CollisionTile {
+		CollisionTile { tile_type: tile_type, row: row, col: col }
+	}
+}
+
+type TileSprite = Rc<RefCell<Box<sprite::Updatable<units::Game>>>>;
+
+/// The frames of an animated tile, shared by every tile of its kind.
+///
+/// Each tile keeps its own `phase` so that neighbouring tiles need not
+/// advance their frames in perfect sync.
+#[derive(Clone)]
+struct TileFrames {
+	sprites:     Rc<Vec<TileSprite>>,
+	frame_time:  units::Millis,
+	phase:       units::Millis,
+}
+
+impl TileFrames {
+	/// Selects the frame to show once the map's clock reads `clock`
+	fn current(&self, clock: units::Millis) -> &TileSprite {
+		let (units::Millis(clock), units::Millis(phase), units::Millis(frame_time)) =
+			(clock, self.phase, self.frame_time);
+
+		let frame = ((clock + phase) / frame_time) as usize;
+		&self.sprites[frame % self.sprites.len()]
+	}
+}
+
+// TODO: Conflicts w/ units::Tile, should probably have a different name.
+#[derive(Clone)]
+struct Tile {
+	tile_type:  TileType,
+	sprite:     Option<TileSprite>,
+	frames:     Option<TileFrames>,
+}
+
+impl Tile {
+	/// Creates n air tile w/ no sprite.
+	fn new() -> Tile {
+		Tile { tile_type: TileType::Air, sprite: None, frames: None }
+	}
+
+	/// Creates a tile of `tile_type` initialized w/ its optional sprite.
+	fn from_sprite(sprite: TileSprite,
+	               tile_type: TileType) -> Tile {
+		Tile { tile_type: tile_type, sprite: Some(sprite.clone()), frames: None }
+	}
+
+	/// Creates a tile of `tile_type` which cycles through `frames`.
+	fn from_frames(frames: TileFrames,
+	               tile_type: TileType) -> Tile {
+		Tile { tile_type: tile_type, sprite: None, frames: Some(frames) }
+	}
+
+	fn draw(&self, graphics: &mut graphics::Graphics,
+	        clock: units::Millis, coords: (units::Game, units::Game)) {
+		let sprite = match (&self.sprite, &self.frames) {
+			(_, &Some(ref frames)) => frames.current(clock),
+			(&Some(ref sprite), _) => sprite,
+			_ => return,
+		};
+
+		sprite.borrow_mut().draw(graphics, coords);
+	}
+}
+
+pub struct Map {
+	background:  Box<backdrop::Backdrop>,
+	sprites:     Vec<Vec<Tile>>,
+	tiles:       Vec<Vec<Tile>>,
+	clock:       units::Millis,
+
+	/// Everything below this line is submerged, regardless of its tiles.
+	water_level: Option<units::Game>,
+	/// Tiles which are submerged, regardless of their type.
+	flooded:     Vec<Vec<bool>>,
+
+	start:        (units::Tile, units::Tile),
+	save_points:  Vec<(units::Tile, units::Tile)>,
+	enemies:      Vec<(String, units::Tile, units::Tile)>,
+}
+
+/// Where the player appears on maps which do not say otherwise: the
+/// middle of the first screen, or of the map if it is any smaller.
+fn default_start(cols: usize, rows: usize) -> (units::Tile, units::Tile) {
+	let (units::Tile(col), units::Tile(row)) =
+		(game::SCREEN_WIDTH / units::Tile(2), game::SCREEN_HEIGHT / units::Tile(2));
+
+	(units::Tile(cmp::min(col, cols / 2)), units::Tile(cmp::min(row, rows / 2)))
+}
+
+impl Map {
+	/// Will initialize a map (20 * 15) tiles:
+	///
+	/// * Most of these tiles will be `Air` tiles.
+	/// * There are 15-tile high walls in the first and last columns. 
+	/// * A small "obstacle course", 5-tiles wide, is placed about 2 tiles in.
+	/// * A 3-tile high chain is placed on the left-side of this obstacle course.
+	/// * A cave bat flutters a third of the way across.
+	pub fn create_test_map(graphics: &mut graphics::Graphics) -> Map {
+		static ROWS: usize = 15; // 480
+		static COLS: usize = 20; // 640
+
+		let map_path =  format!("assets/base/Stage/PrtCave.bmp");
+		let sprite   =  Rc::new(RefCell::new(
+			Box::new(sprite::Sprite::new(
+				graphics,
+				(units::Tile(1) , units::Tile(0)),
+				(units::Tile(1), units::Tile(1)),
+				map_path.clone()
+			)) as Box<sprite::Updatable<_>>
+		));
+
+		let chain_top = Rc::new(RefCell::new(
+			Box::new(sprite::Sprite::new(
+				graphics,
+				(units::Tile(11), units::Tile(2)),
+				(units::Tile(1), units::Tile(1)),
+				map_path.clone()
+			)) as Box<sprite::Updatable<_>>
+		));
+
+		let chain_middle = Rc::new(RefCell::new(
+			Box::new(sprite::Sprite::new(
+				graphics,
+				(units::Tile(12), units::Tile(2)),
+				(units::Tile(1), units::Tile(1)),
+				map_path.clone()
+			)) as Box<sprite::Updatable<_>>
+		));
+
+		let chain_bottom = Rc::new(RefCell::new(
+			Box::new(sprite::Sprite::new(
+				graphics, 
+				(units::Tile(13), units::Tile(2)),
+				(units::Tile(1), units::Tile(1)),
+				map_path.clone()
+			)) as Box<sprite::Updatable<_>>
+		));
+
+		let blank_tile = Tile::new();
+		let wall_tile  = Tile::from_sprite(sprite, TileType::Wall);
+		let ct_tile    = Tile::from_sprite(chain_top, TileType::Air);
+		let cm_tile    = Tile::from_sprite(chain_middle, TileType::Air);
+		let cb_tile    = Tile::from_sprite(chain_bottom, TileType::Air);
+
+		let blank_row: Vec<Tile> = repeat(blank_tile).take(COLS).collect();
+
+		let mut map = Map {
+			background: Box::new(backdrop::FixedBackdrop::new(
+				format!("assets/base/bkBlue.bmp"), graphics
+			)),
+			sprites: repeat(blank_row.clone()).take(ROWS).collect(),
+			tiles: repeat(blank_row.clone()).take(ROWS).collect(),
+			clock: units::Millis(0),
+			water_level: None,
+			flooded: vec![vec![false; COLS]; ROWS],
+
+			start: default_start(COLS, ROWS),
+			save_points: Vec::new(),
+			enemies: vec![(format!("bat"), game::SCREEN_WIDTH / units::Tile(3), units::Tile(10))],
+		};
+
+	
+		// init `floor`
+		for i in 0..COLS {
+			map.tiles[ROWS - 1][i] = wall_tile.clone();
+		}
+
+		// "safety wall"
+		for i in 0..ROWS {
+			map.tiles[i][0]        = wall_tile.clone();
+			map.tiles[i][COLS - 1] = wall_tile.clone();
+		}
+
+
+		map.tiles[ROWS - 2][3] = wall_tile.clone();
+		map.tiles[ROWS - 2][5] = wall_tile.clone();
+
+		map.tiles[ROWS - 3][4] = wall_tile.clone();
+		map.tiles[ROWS - 4][3] = wall_tile.clone();
+		map.tiles[ROWS - 5][2] = wall_tile.clone();
+
+		map.sprites[ROWS - 4][2] = ct_tile.clone();
+		map.sprites[ROWS - 3][2] = cm_tile.clone();
+		map.sprites[ROWS - 2][2] = cb_tile.clone();
+	
+		map
+	}
+
+	/// Reads the map file found at `path`, see `loader::MapFile` for its format.
+	///
+	/// Returns a description of the problem if the file could not be read,
+	/// or if it is not a valid map.
+	pub fn load(graphics: &mut graphics::Graphics, path: &Path) -> Result<Map, String> {
+		let mut text = String::new();
+		let mut file = try!(File::open(path).map_err(|err| {
+			format!("{}: {}", path.display(), err)
+		}));
+
+		try!(file.read_to_string(&mut text).map_err(|err| {
+			format!("{}: {}", path.display(), err)
+		}));
+
+		let map_file = try!(loader::parse(&text[..]).map_err(|msg| {
+			format!("{}: {}", path.display(), msg)
+		}));
+
+		Ok(Map::from_file(graphics, &map_file))
+	}
+
+	/// Reads an original Cave Story stage: its `.pxm` layout and the
+	/// `.pxa` attributes of its tileset. (See `stage` for details.)
+	pub fn load_stage(graphics: &mut graphics::Graphics,
+	                  pxm_path: &Path, pxa_path: &Path,
+	                  tileset: String, backdrop: String) -> Result<Map, String> {
+		let map_file = try!(stage::load(pxm_path, pxa_path, tileset, backdrop));
+		Ok(Map::from_file(graphics, &map_file))
+	}
+
+	/// Loads the sprites referenced by `map_file` and lays out its tiles.
+	/// Tiles which share an index into the tileset will share a sprite.
+	///
+	/// Animated tiles share their frames, but are each given a phase
+	/// based on their position in the map.
+	pub fn from_file(graphics: &mut graphics::Graphics, map_file: &loader::MapFile) -> Map {
+		let mut cache  = HashMap::<usize, TileSprite>::new();
+		let mut frames = HashMap::<usize, Rc<Vec<TileSprite>>>::new();
+
+		let mut tile_at = |index: Option<usize>, tile_type: TileType,
+		                   row: usize, col: usize| -> Tile {
+			let index = match index {
+				Some(index) => index,
+				None => return Tile { tile_type: tile_type, sprite: None, frames: None },
+			};
+
+			let mut sprite_at = |index: usize| -> TileSprite {
+				match cache.entry(index) {
+					Entry::Occupied(entry) => entry.get().clone(),
+					Entry::Vacant(entry) => {
+						entry.insert(Rc::new(RefCell::new(
+							Box::new(sprite::Sprite::new(
+								graphics,
+								(units::Tile(index % loader::TILESET_COLUMNS),
+								 units::Tile(index / loader::TILESET_COLUMNS)),
+								(units::Tile(1), units::Tile(1)),
+								map_file.tileset.clone()
+							)) as Box<sprite::Updatable<_>>
+						))).clone()
+					},
+				}
+			};
+
+			match map_file.animations.get(&index) {
+				Some(animation) => {
+					// frames are laid out left-to-right, starting at `index`
+					let sprites = match frames.entry(index) {
+						Entry::Occupied(entry) => entry.get().clone(),
+						Entry::Vacant(entry) => {
+							let sprites = (0..animation.num_frames).map(|frame| {
+								sprite_at(index + frame as usize)
+							}).collect();
+
+							entry.insert(Rc::new(sprites)).clone()
+						},
+					};
+
+					let (units::Millis(phase_x), units::Millis(phase_y)) =
+						(animation.phase_x, animation.phase_y);
+
+					Tile::from_frames(TileFrames {
+						sprites:    sprites,
+						frame_time: animation.frame_time,
+						phase:      units::Millis((phase_x * col as i64) + (phase_y * row as i64)),
+					}, tile_type)
+				},
+
+				None => Tile::from_sprite(sprite_at(index), tile_type),
+			}
+		};
+
+		let mut flooded = vec![vec![false; map_file.width]; map_file.height];
+		for &(col, row) in map_file.flooded.iter() {
+			flooded[row][col] = true;
+		}
+
+		let mut tiles   = Vec::with_capacity(map_file.height);
+		let mut sprites = Vec::with_capacity(map_file.height);
+		for row in 0..map_file.height {
+			tiles.push((0..map_file.width).map(|col| {
+				tile_at(map_file.tiles[row][col], map_file.collision[row][col], row, col)
+			}).collect());
+
+			sprites.push((0..map_file.width).map(|col| {
+				tile_at(map_file.sprites[row][col], TileType::Air, row, col)
+			}).collect());
+		}
+
+		Map {
+			background: backdrop::from_layers(&map_file.backdrops[..], graphics),
+			sprites: sprites,
+			tiles: tiles,
+			clock: units::Millis(0),
+			water_level: map_file.water_level.map(|row| units::Tile(row).to_game()),
+			flooded: flooded,
+
+			start: map_file.start.map_or(default_start(map_file.width, map_file.height), |(col, row)| {
+				(units::Tile(col), units::Tile(row))
+			}),
+			save_points: map_file.save_points.iter().map(|&(col, row)| {
+				(units::Tile(col), units::Tile(row))
+			}).collect(),
+			enemies: map_file.enemies.iter().map(|&(ref kind, col, row)| {
+				(kind.clone(), units::Tile(col), units::Tile(row))
+			}).collect(),
+		}
+	}
+
+	/// Draws the backdrop in screen-space, scrolled according to the camera.
+	pub fn draw_background(&mut self, graphics: &mut graphics::Graphics) {
+		let (camera_x, camera_y) = graphics.camera();
+
+		graphics.reset_camera();
+		self.background.draw(graphics, (camera_x, camera_y));
+		graphics.set_camera(camera_x, camera_y);
+	}
+
+	pub fn draw_sprites(&mut self, graphics: &mut graphics::Graphics) {
+		let (rows, cols) = self.visible_tiles(graphics);
+		for a in rows {
+			for b in cols.clone() {
+				self.sprites[a][b].draw(graphics, self.clock,
+				                        (units::Tile(b).to_game(),
+				                         units::Tile(a).to_game()));
+			}
+		}
+	}
+
+	/// Draws current state to `display`
+	pub fn draw(&mut self, graphics: &mut graphics::Graphics) {
+		let (rows, cols) = self.visible_tiles(graphics);
+		for a in rows {
+			for b in cols.clone() {
+				self.tiles[a][b].draw(graphics, self.clock,
+				                      (units::Tile(b).to_game(),
+				                       units::Tile(a).to_game()));
+			}
+		}
+	}
+
+	/// Width of the map in game units.
+	pub fn width(&self) -> units::Game {
+		units::Tile(self.tiles.first().map_or(0, |row| row.len())).to_game()
+	}
+
+	/// Height of the map in game units.
+	pub fn height(&self) -> units::Game {
+		units::Tile(self.tiles.len()).to_game()
+	}
+
+	/// The rows & columns of tiles which fall within view of the camera.
+	fn visible_tiles(&self, graphics: &graphics::Graphics) -> (Range<usize>, Range<usize>) {
+		let (cam_x, cam_y) = graphics.camera();
+		let (units::Game(x), units::Game(y)) = (cam_x, cam_y);
+		let (x, y) = (units::Game(x.max(0.0)), units::Game(y.max(0.0)));
+
+		let (units::Tile(first_row), units::Tile(first_col)) = (y.to_tile(), x.to_tile());
+		let units::Tile(last_row) = (y + game::SCREEN_HEIGHT).to_tile() + units::Tile(1);
+		let units::Tile(last_col) = (x + game::SCREEN_WIDTH).to_tile() + units::Tile(1);
+
+		let rows = self.tiles.len();
+		let cols = self.tiles.first().map_or(0, |row| row.len());
+
+		(cmp::min(first_row, rows)..cmp::min(last_row, rows),
+		 cmp::min(first_col, cols)..cmp::min(last_col, cols))
+	}
+
+	/// Animates the backdrop & advances the clock of animated tiles.
+	pub fn update(&mut self, elapsed_time: units::Millis) {
+		self.background.update(elapsed_time);
+
+		/* 
+		 * Tile sprites are shared, so they are never updated directly:
+		 * updating a shared `AnimatedSprite` would advance every tile
+		 * using it in perfect sync.
+		 * 
+		 * Instead animated tiles pick their frame from this clock,
+		 * offset by their own phase.
+		 */
+		self.clock = self.clock + elapsed_time;
+	}
+
+	/// Finds the floor slope at map x-coordinate `x` whose incline lies
+	/// between `top` and `bottom`, returning the y-coordinate of that incline.
+	///
+	/// If several do, the highest incline is returned.
+	pub fn floor_slope_at(&self, x: units::Game, top: units::Game, bottom: units::Game) -> Option<units::Game> {
+		self.slopes_at(x, top, bottom, Surface::Floor).into_iter()
+			.filter(|&y| y >= top && y <= bottom)
+			.next()
+	}
+
+	/// Finds the ceiling slope at map x-coordinate `x` whose incline lies
+	/// between `top` and `bottom`, returning the y-coordinate of that incline.
+	///
+	/// If several do, the lowest incline is returned.
+	pub fn ceiling_slope_at(&self, x: units::Game, top: units::Game, bottom: units::Game) -> Option<units::Game> {
+		self.slopes_at(x, top, bottom, Surface::Ceiling).into_iter()
+			.filter(|&y| y >= top && y <= bottom)
+			.last()
+	}
+
+	/// The inclines of every `surface` slope in the column at `x`,
+	/// for the rows spanning `top` to `bottom`, from top to bottom.
+	fn slopes_at(&self, x: units::Game, top: units::Game, bottom: units::Game,
+	             surface: Surface) -> Vec<units::Game> {
+		if x < units::Game(0.0) || x >= self.width() { return vec![] }
+
+		let col = x.to_tile();
+		let units::Tile(col_idx)   = col;
+		let units::Tile(first_row) = if top < units::Game(0.0) { units::Tile(0) } else { top.to_tile() };
+		let units::Tile(last_row)  = bottom.to_tile();
+		let last_row = cmp::min(last_row + 1, self.tiles.len());
+
+		(first_row..last_row).filter_map(|row| {
+			match self.tiles[row][col_idx].tile_type {
+				TileType::Slope(slope) if slope.surface == surface =>
+					Some(slope.surface_at(units::Tile(row), col, x)),
+				_ => None,
+			}
+		}).collect()
+	}
+
+	/// Where the player first appears on this map.
+	pub fn start(&self) -> (units::Game, units::Game) {
+		let (col, row) = self.start;
+		(col.to_game(), row.to_game())
+	}
+
+	/// The kind of each enemy placed on this map, & where it starts.
+	pub fn enemies(&self) -> Vec<(&str, units::Game, units::Game)> {
+		self.enemies.iter().map(|&(ref kind, col, row)| {
+			(&kind[..], col.to_game(), row.to_game())
+		}).collect()
+	}
+
+	/// The first save point touching `rectangle`, if any.
+	pub fn save_point_at(&self, rectangle: &Rectangle) -> Option<(units::Game, units::Game)> {
+		self.save_points.iter().map(|&(col, row)| (col.to_game(), row.to_game()))
+			.filter(|&(x, y)| {
+				let tile = Rectangle {
+					x: x, y: y,
+					width: units::Tile(1).to_game(), height: units::Tile(1).to_game(),
+				};
+				tile.collides_with(rectangle)
+			}).next()
+	}
+
+	/// Moves the surface of the water, `None` drains the map entirely.
+	/// (Water tiles are unaffected.)
+	pub fn set_water_level(&mut self, water_level: Option<units::Game>) {
+		self.water_level = water_level;
+	}
+
+	/// Whether the point at `x`, `y` is under water.
+	pub fn is_submerged(&self, x: units::Game, y: units::Game) -> bool {
+		if self.water_level.map_or(false, |level| y >= level) {
+			return true;
+		}
+
+		if x < units::Game(0.0) || y < units::Game(0.0)
+		|| x >= self.width() || y >= self.height() {
+			return false;
+		}
+
+		let (units::Tile(row), units::Tile(col)) = (y.to_tile(), x.to_tile());
+		self.tiles[row][col].tile_type == TileType::Water || self.flooded[row][col]
+	}
+
+	/// The most damage dealt by any hazardous tile touching `rectangle`
+	pub fn hazard_damage(&self, rectangle: &Rectangle) -> Option<units::HitPoints> {
+		self.get_colliding_tiles(rectangle).iter()
+			.filter_map(|tile| tile.tile_type.damage())
+			.max()
+	}
+
+	/// Checks if `Rectangle` is colliding with any tiles in the foreground.
+	/// 
+	/// NOTE: Only tiles within the tile-map are reported: whatever lies
+	/// beyond an edge which is not walled off is empty space.
+	/// 
+	/// NOTE: This is a simple check of the _outside bounds_ of the
+	/// rectangle & tile. -- This method may claim that the player is 
+	/// colliding w/ the edge of a tile that _appears to be_ empty space.
+	pub fn get_colliding_tiles(&self, rectangle: &Rectangle) -> Vec<CollisionTile> {
+		let mut collision_tiles: Vec<CollisionTile> = Vec::new();
+		if rectangle.bottom() < units::Game(0.0) || rectangle.right() < units::Game(0.0) {
+			return collision_tiles;
+		}
+
+		let (units::Game(top), units::Game(left)) = (rectangle.top(), rectangle.left());
+		let (top, left) = (units::Game(top.max(0.0)), units::Game(left.max(0.0)));
+
+		let (units::Tile(first_row), units::Tile(first_col)) = (top.to_tile(), left.to_tile());
+		let units::Tile(last_row) = rectangle.bottom().to_tile() + units::Tile(1);
+		let units::Tile(last_col) = rectangle.right().to_tile() + units::Tile(1);
+
+		let rows = self.tiles.len();
+		let cols = self.tiles.first().map_or(0, |row| row.len());
+
+		for row in cmp::min(first_row, rows)..cmp::min(last_row, rows) {
+			for col in cmp::min(first_col, cols)..cmp::min(last_col, cols) {
+				collision_tiles.push( 
+					CollisionTile::new(units::Tile(row), units::Tile(col), self.tiles[row][col].tile_type)
+				);
+			}
+		}
+
+		collision_tiles
+	}
+}
+
+#[test]
+fn test_load_matches_test_map() {
+	let mut display = graphics::Graphics::headless();
+	let test_map = Map::create_test_map(&mut display);
+	let loaded   = Map::load(&mut display, Path::new("assets/maps/test.map")).unwrap();
+
+	assert_eq!(loaded.tiles.len(), test_map.tiles.len());
+	for (row, tiles) in loaded.tiles.iter().enumerate() {
+		for (col, tile) in tiles.iter().enumerate() {
+			assert!(tile.tile_type == test_map.tiles[row][col].tile_type);
+			assert_eq!(tile.sprite.is_some(), test_map.tiles[row][col].sprite.is_some());
+			assert_eq!(loaded.sprites[row][col].sprite.is_some(),
+			           test_map.sprites[row][col].sprite.is_some());
+		}
+	}
+}
+
+#[test]
+fn test_animated_tiles_have_phase() {
+	let map_file = loader::parse("
+		backdrop  bk.bmp
+		tileset   tiles.bmp
+		animate   32 4 100 50 0
+		size      2 1
+
+		[tiles]
+		32 32
+	").unwrap();
+
+	let mut display = graphics::Graphics::headless();
+	let mut map = Map::from_file(&mut display, &map_file);
+
+	fn frames(map: &mut Map, display: &mut graphics::Graphics) -> Vec<i32> {
+		display.clear_buffer();
+		map.draw(display);
+		display.headless_backend().unwrap().blits().iter().map(|blit| {
+			blit.src_rect.x() / 32
+		}).collect()
+	}
+
+	assert_eq!(frames(&mut map, &mut display), vec![0, 0]);
+	map.update(units::Millis(250));
+	assert_eq!(frames(&mut map, &mut display), vec![2, 3]);
+	map.update(units::Millis(200));
+	assert_eq!(frames(&mut map, &mut display), vec![0, 1]);
+}
+
+#[test]
+fn test_spikes_deal_damage() {
+	let map_file = loader::parse("
+		backdrop  bk.bmp
+		tileset   tiles.bmp
+		size      3 2
+
+		[collision]
+		...
+		#^#
+	").unwrap();
+
+	let mut display = graphics::Graphics::headless();
+	let map = Map::from_file(&mut display, &map_file);
+
+	let touching = Rectangle {
+		x: units::Game(40.0), y: units::Game(20.0),
+		width: units::Game(16.0), height: units::Game(16.0),
+	};
+	let above = Rectangle { y: units::Game(0.0), .. touching };
+
+	assert_eq!(map.hazard_damage(&touching), Some(SPIKE_DAMAGE));
+	assert_eq!(map.hazard_damage(&above), None);
+}
@@ -0,0 +1,198 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+use backdrop::Layer;
+use map::{Slope, TileType};
+use map::loader::MapFile;
+
+/// Attributes at or above this value are drawn in front of the player.
+static FOREGROUND: u8 = 0x40;
+
+/// Foreground attributes w/ this bit set are under water.
+static SUBMERGED: u8 = 0x20;
+
+/// The layout of a Cave Story stage, as stored in a `.pxm` file.
+///
+/// A `.pxm` starts w/ the magic `PXM\x10`, followed by the width and height
+/// of the stage as little-endian `u16`s, followed by one byte per tile: the
+/// index of that tile in the stage's tileset.
+pub struct Pxm {
+	pub width:   usize,
+	pub height:  usize,
+	pub tiles:   Vec<u8>,
+}
+
+/// Parses the contents of a `.pxm` file.
+pub fn parse_pxm(bytes: &[u8]) -> Result<Pxm, String> {
+	if bytes.len() < 8 || &bytes[0..4] != b"PXM\x10" {
+		return Err(format!("not a PXM file"));
+	}
+
+	let width  = (bytes[4] as usize) | ((bytes[5] as usize) << 8);
+	let height = (bytes[6] as usize) | ((bytes[7] as usize) << 8);
+	let tiles  = &bytes[8..];
+
+	if width == 0 || height == 0 {
+		return Err(format!("PXM is {}x{} tiles, a stage must be at least 1x1", width, height));
+	}
+
+	if tiles.len() < width * height {
+		return Err(format!("PXM is truncated: expected {} tiles, found {}",
+		                   width * height, tiles.len()));
+	}
+
+	Ok(Pxm { width: width, height: height, tiles: tiles[..(width * height)].to_vec() })
+}
+
+/// Parses the contents of a `.pxa` file.
+///
+/// A `.pxa` is simply one attribute byte for every tile of the tileset;
+/// so attribute `n` describes the tile at index `n` of the tileset.
+pub fn parse_pxa(bytes: &[u8]) -> Result<Vec<u8>, String> {
+	if bytes.is_empty() {
+		return Err(format!("PXA is empty"));
+	}
+
+	Ok(bytes.to_vec())
+}
+
+/// Maps a Cave Story tile attribute onto its `TileType`.
+///
+/// Anything which is not understood yet behaves as air. Whether the tile
+/// is also under water is told by `is_submerged_attribute()`.
+pub fn tile_type_from_attribute(attribute: u8) -> TileType {
+	match attribute {
+		0x41 | 0x43 | 0x46 => TileType::Wall, // solid, breakable, player-only solid
+		0x61               => TileType::Wall, // solid (submerged)
+		0x42 | 0x62        => TileType::Spikes, // spikes (incl. submerged)
+		0x02 | 0x60        => TileType::Water,  // water (behind & in front of the player)
+		0x50...0x57 | 0x70...0x77 =>          // slopes (incl. submerged)
+			Slope::from_index(attribute & 0x07).map_or(TileType::Air, TileType::Slope),
+		_                  => TileType::Air,
+	}
+}
+
+/// Whether a Cave Story tile attribute lies under water, e.g: `0x61` is a
+/// submerged wall & `0x70` - `0x77` are submerged slopes.
+pub fn is_submerged_attribute(attribute: u8) -> bool {
+	attribute >= FOREGROUND && attribute & SUBMERGED != 0
+}
+
+/// Combines a stage layout w/ its tileset's attributes.
+///
+/// Tiles w/ a background attribute are placed on the `[sprites]` layer,
+/// which is drawn behind the player; all others are placed on `[tiles]`.
+pub fn to_map_file(pxm: &Pxm, pxa: &[u8],
+                   tileset: String, backdrop: String) -> Result<MapFile, String> {
+	let mut tiles     = vec![vec![None; pxm.width]; pxm.height];
+	let mut sprites   = vec![vec![None; pxm.width]; pxm.height];
+	let mut collision = vec![vec![TileType::Air; pxm.width]; pxm.height];
+	let mut flooded   = Vec::new();
+
+	for row in 0..pxm.height {
+		for col in 0..pxm.width {
+			let index = pxm.tiles[row * pxm.width + col] as usize;
+			let attribute = match pxa.get(index) {
+				Some(attribute) => *attribute,
+				None => return Err(format!("tile {} at ({}, {}) has no attribute in the PXA",
+				                           index, col, row)),
+			};
+
+			// tile zero is the empty tile in every Cave Story tileset
+			if index != 0 {
+				if attribute >= FOREGROUND { tiles[row][col] = Some(index); }
+				else { sprites[row][col] = Some(index); }
+			}
+
+			collision[row][col] = tile_type_from_attribute(attribute);
+			if is_submerged_attribute(attribute) { flooded.push((col, row)); }
+		}
+	}
+
+	Ok(MapFile {
+		backdrops: vec![Layer::Fixed(backdrop)], tileset: tileset,
+		width: pxm.width, height: pxm.height,
+		animations: HashMap::new(), water_level: None,
+		start: None, save_points: Vec::new(), enemies: Vec::new(),
+		flooded: flooded,
+		tiles: tiles, collision: collision, sprites: sprites,
+	})
+}
+
+/// Reads a stage from its `.pxm` layout & `.pxa` attribute table.
+pub fn load(pxm_path: &Path, pxa_path: &Path,
+            tileset: String, backdrop: String) -> Result<MapFile, String> {
+	let pxm = try!(read_file(pxm_path).and_then(|bytes| {
+		parse_pxm(&bytes[..]).map_err(|msg| format!("{}: {}", pxm_path.display(), msg))
+	}));
+
+	let pxa = try!(read_file(pxa_path).and_then(|bytes| {
+		parse_pxa(&bytes[..]).map_err(|msg| format!("{}: {}", pxa_path.display(), msg))
+	}));
+
+	to_map_file(&pxm, &pxa[..], tileset, backdrop)
+}
+
+fn read_file(path: &Path) -> Result<Vec<u8>, String> {
+	let mut bytes = Vec::new();
+	let mut file = try!(File::open(path).map_err(|err| {
+		format!("{}: {}", path.display(), err)
+	}));
+
+	try!(file.read_to_end(&mut bytes).map_err(|err| {
+		format!("{}: {}", path.display(), err)
+	}));
+
+	Ok(bytes)
+}
+
+#[test]
+fn test_parse_pxm() {
+	let pxm = parse_pxm(b"PXM\x10\x03\x00\x02\x00\x00\x01\x02\x10\x11\x12").unwrap();
+	assert_eq!((pxm.width, pxm.height), (3, 2));
+	assert_eq!(pxm.tiles[4], 0x11);
+
+	assert!(parse_pxm(b"PXM\x10\x03\x00\x02\x00\x00").is_err());
+	assert!(parse_pxm(b"BMP\x10\x00\x00\x00\x00").is_err());
+	assert!(parse_pxm(b"PXM\x10\x00\x00\x00\x00").is_err());
+	assert!(parse_pxm(b"PXM\x10\x00\x00\x02\x00").is_err());
+}
+
+#[test]
+fn test_stage_layers() {
+	let pxm = parse_pxm(b"PXM\x10\x03\x00\x01\x00\x00\x01\x02").unwrap();
+	let pxa = [0x00, 0x41, 0x01];
+	let map = to_map_file(&pxm, &pxa, format!("tiles.bmp"), format!("bk.bmp")).unwrap();
+
+	assert_eq!(map.tiles[0],   vec![None, Some(1), None]);
+	assert_eq!(map.sprites[0], vec![None, None, Some(2)]);
+	assert!(map.collision[0] == vec![TileType::Air, TileType::Wall, TileType::Air]);
+}
+
+#[test]
+fn test_submerged_attributes() {
+	use graphics;
+	use map::Map;
+	use units::{self, AsGame};
+
+	let pxm = parse_pxm(b"PXM\x10\x05\x00\x01\x00\x01\x02\x03\x04\x05").unwrap();
+	let pxa = [0x00, 0x41, 0x61, 0x62, 0x70, 0x60];
+	let map = to_map_file(&pxm, &pxa, format!("tiles.bmp"), format!("bk.bmp")).unwrap();
+
+	assert!(map.collision[0][1] == TileType::Wall);
+	assert!(map.collision[0][2] == TileType::Spikes);
+	assert!(map.collision[0][3] == TileType::Slope(Slope::from_index(0).unwrap()));
+	assert!(map.collision[0][4] == TileType::Water);
+	assert_eq!(map.flooded, vec![(1, 0), (2, 0), (3, 0), (4, 0)]);
+
+	// the player is still under water while touching them
+	let map = Map::from_file(&mut graphics::Graphics::headless(), &map);
+	let middle = units::Game(16.0);
+	assert!(!map.is_submerged(units::Tile(1).to_game() - middle, middle));
+	assert!((1..5).all(|col| map.is_submerged(units::Tile(col).to_game() + middle, middle)));
+
+	// a stage smaller than the screen starts the player within it
+	assert!(map.start() == (units::Tile(2).to_game(), units::Tile(0).to_game()));
+}
@@ -0,0 +1,178 @@
+use std::f64::consts::PI;
+
+use graphics;
+use number_sprite::NumberSprite;
+use sprite::{Drawable, Sprite};
+
+use units;
+use units::AsGame;
+
+static SMOKE_SHEET: &'static str   = "assets/base/Caret.bmp";
+static SMOKE_FRAMES: usize         = 7;
+static SMOKE_FRAME_TIME: units::Millis  = units::Millis(60);
+static SMOKE_SPEED: units::Velocity     = units::Velocity(0.08);
+
+static DAMAGE_NUMBER_TIME: units::Millis     = units::Millis(800);
+static DAMAGE_NUMBER_RISE: units::Millis     = units::Millis(200);
+static DAMAGE_NUMBER_SPEED: units::Velocity  = units::Velocity(-0.1);
+
+/// A short-lived, purely cosmetic effect drawn in map-space.
+pub trait Particle {
+	/// Returns `false` once the particle has finished & may be discarded.
+	fn update(&mut self, elapsed_time: units::Millis) -> bool;
+	fn draw(&mut self, graphics: &mut graphics::Graphics);
+}
+
+/// Owns every particle currently alive.
+pub struct ParticleSystem {
+	particles: Vec<Box<Particle>>,
+}
+
+impl ParticleSystem {
+	pub fn new() -> ParticleSystem {
+		ParticleSystem { particles: Vec::new() }
+	}
+
+	pub fn add(&mut self, particle: Box<Particle>) {
+		self.particles.push(particle);
+	}
+
+	pub fn clear(&mut self) {
+		self.particles.clear();
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.particles.is_empty()
+	}
+
+	pub fn update(&mut self, elapsed_time: units::Millis) {
+		let particles = ::std::mem::replace(&mut self.particles, Vec::new());
+		self.particles = particles.into_iter()
+			.filter_map(|mut particle| {
+				if particle.update(elapsed_time) { Some(particle) } else { None }
+			}).collect();
+	}
+
+	pub fn draw(&mut self, graphics: &mut graphics::Graphics) {
+		for particle in self.particles.iter_mut() {
+			particle.draw(graphics);
+		}
+	}
+}
+
+/// A puff of smoke which drifts away from where it was spawned
+/// while it plays out its animation once.
+pub struct Smoke {
+	frames: Vec<Sprite>,
+	x: units::Game,
+	y: units::Game,
+	velocity_x: units::Velocity,
+	velocity_y: units::Velocity,
+	age: units::Millis,
+}
+
+impl Smoke {
+	/// A puff centered on `x`, `y` drifting in the direction of `angle` (in radians)
+	pub fn new(graphics: &mut graphics::Graphics,
+	           x: units::Game, y: units::Game, angle: f64) -> Smoke {
+		let frames = (0..SMOKE_FRAMES).map(|frame| {
+			Sprite::new(graphics,
+			            (units::Tile(frame + 1), units::Tile(0)),
+			            (units::Tile(1), units::Tile(1)),
+			            SMOKE_SHEET.to_string())
+		}).collect();
+
+		let units::Velocity(speed) = SMOKE_SPEED;
+		Smoke {
+			frames: frames,
+			x: x - units::HalfTile(1), y: y - units::HalfTile(1),
+			velocity_x: units::Velocity(speed * angle.cos()),
+			velocity_y: units::Velocity(speed * angle.sin()),
+			age: units::Millis(0),
+		}
+	}
+
+	/// A ring of `count` puffs bursting outwards from `x`, `y`
+	pub fn burst(graphics: &mut graphics::Graphics,
+	             x: units::Game, y: units::Game, count: usize) -> Vec<Box<Particle>> {
+		(0..count).map(|idx| {
+			let angle = 2.0 * PI * (idx as f64 / count as f64);
+			Box::new(Smoke::new(graphics, x, y, angle)) as Box<Particle>
+		}).collect()
+	}
+
+	fn frame(&self) -> usize {
+		let (units::Millis(age), units::Millis(frame_time)) = (self.age, SMOKE_FRAME_TIME);
+		(age / frame_time) as usize
+	}
+}
+
+impl Particle for Smoke {
+	fn update(&mut self, elapsed_time: units::Millis) -> bool {
+		self.age = self.age + elapsed_time;
+		self.x = self.x + (self.velocity_x * elapsed_time);
+		self.y = self.y + (self.velocity_y * elapsed_time);
+
+		self.frame() < self.frames.len()
+	}
+
+	fn draw(&mut self, graphics: &mut graphics::Graphics) {
+		let frame = self.frame();
+		if let Some(sprite) = self.frames.get_mut(frame) {
+			sprite.draw(graphics, (self.x, self.y));
+		}
+	}
+}
+
+/// The damage dealt by a hit: it rises briefly from where it was
+/// dealt, then lingers there.
+pub struct DamageNumber {
+	number: NumberSprite,
+	x: units::Game,
+	y: units::Game,
+	age: units::Millis,
+}
+
+impl DamageNumber {
+	/// `damage` centered above `x`, `y`
+	pub fn new(graphics: &mut graphics::Graphics, damage: units::HitPoints,
+	           x: units::Game, y: units::Game) -> DamageNumber {
+		DamageNumber {
+			number: NumberSprite::new(graphics, damage),
+			x: x - units::HalfTile(1).to_game() / units::Game(2.0),
+			y: y - units::HalfTile(1),
+			age: units::Millis(0),
+		}
+	}
+}
+
+impl Particle for DamageNumber {
+	fn update(&mut self, elapsed_time: units::Millis) -> bool {
+		if self.age < DAMAGE_NUMBER_RISE {
+			self.y = self.y + (DAMAGE_NUMBER_SPEED * elapsed_time);
+		}
+
+		self.age = self.age + elapsed_time;
+		self.age < DAMAGE_NUMBER_TIME
+	}
+
+	fn draw(&mut self, graphics: &mut graphics::Graphics) {
+		self.number.draw(graphics, (self.x, self.y));
+	}
+}
+
+#[test]
+fn test_smoke_expires() {
+	let mut display = graphics::Graphics::headless();
+	let mut particles = ParticleSystem::new();
+	for puff in Smoke::burst(&mut display, units::Game(64.0), units::Game(64.0), 8) {
+		particles.add(puff);
+	}
+
+	particles.update(units::Millis(100));
+	assert!(!particles.is_empty());
+
+	let units::Millis(frame_time) = SMOKE_FRAME_TIME;
+	particles.update(units::Millis(frame_time * SMOKE_FRAMES as i64));
+	assert!(particles.is_empty());
+}
@@ -1,12 +1,15 @@
+use std::cmp;
 use std::collections::hash_map::{HashMap, Entry};
 use num::Float;
 
 use graphics;
+use hud;
 use number_sprite::NumberSprite;
-use sprite::{self, Facing, Looking, Motion, Updatable};
+use sprite::{self, Drawable, Facing, Looking, Motion, Updatable};
 
 use collisions::{Info,Rectangle};
 use map::{self, TileType};
+use weapons::{self, Projectile};
 
 use units;
 use units::AsGame;
@@ -26,6 +29,17 @@ static JUMP_GRAVITY:     units::Acceleration  = units::Acceleration(0.0003125);
 static JUMP_SPEED:       units::Velocity      = units::Velocity(0.25);
 static SHORT_JUMP_SPEED: units::Velocity      = units::Velocity(0.25 / 1.5);
 
+// physics (submerged)
+static WATER_GRAVITY:        units::Acceleration  = units::Acceleration(0.00078125 / 2.0);
+static WATER_JUMP_GRAVITY:   units::Acceleration  = units::Acceleration(0.0003125 / 2.0);
+static WATER_MAX_VELOCITY_X: units::Velocity      = units::Velocity(0.15859375 / 2.0);
+static WATER_MAX_VELOCITY_Y: units::Velocity      = units::Velocity(0.2998046875 / 2.0);
+static WATER_JUMP_SPEED:     units::Velocity      = units::Velocity(0.25 / 2.0);
+
+// how long the player may stay submerged before they start drowning
+static MAX_AIR:         units::Millis     = units::Millis(20000);
+static DROWNING_DAMAGE: units::HitPoints  = 1;
+
 // player sprite animation
 const  CHAR_OFFSET:        usize         = 12;
 static SPRITE_NUM_FRAMES:  units::Frame  = 3;
@@ -56,39 +70,53 @@ static Y_BOX: Rectangle = Rectangle {
 	width: units::Game(12.0), height: units::Game(30.0)
 };
 
-static DAMAGE_INVINCIBILITY: units::Millis  = units::Millis(3000);
-static INVINCIBILITY_FLASH:  units::Millis  = units::Millis(50);
+// how far a player walking down an incline may be pulled back onto it
+static SLOPE_SNAP: units::Game = units::Game(8.0);
+
+/// A jump pressed this long before landing is performed on landing.
+pub static JUMP_BUFFER: units::Millis  = units::Millis(100);
+/// A jump may still be started this long after walking off a ledge.
+pub static COYOTE_TIME: units::Millis  = units::Millis(80);
 
-static HEALTH_BAR_X: units::Tile           = units::Tile(2);
-static HEALTH_BAR_Y: units::Tile           = units::Tile(2);
-static HEALTH_BAR_OFS_X: units::HalfTile   = units::HalfTile(0);
-static HEALTH_BAR_OFS_Y: units::HalfTile   = units::HalfTile(5);
-static HEALTH_BAR_W: units::HalfTile       = units::HalfTile(8);
-static HEALTH_BAR_H: units::HalfTile       = units::HalfTile(1);
+static MAX_HEALTH: units::HitPoints = 3;
 
-static HEALTH_FILL_X: units::HalfTile          = units::HalfTile(7);
-static HEALTH_FILL_Y: units::HalfTile          = units::HalfTile(4);
-static HEALTH_FILL_OFS_X: units::HalfTile  = units::HalfTile(0);
-static HEALTH_FILL_OFS_Y: units::HalfTile  = units::HalfTile(3);
+/// Each point of damage taken costs the player's weapon this much experience.
+static EXPERIENCE_LOSS: i32 = 2;
 
-static FILL_SHIFT: units::Game         = units::Game(2.0);	
-static HEALTH_FILL_W: units::HalfTile  = units::HalfTile(5);
-static HEALTH_FILL_H: units::HalfTile  = units::HalfTile(1);
+static DAMAGE_INVINCIBILITY: units::Millis  = units::Millis(3000);
+static INVINCIBILITY_FLASH:  units::Millis  = units::Millis(50);
+
+static AIR_LABEL_X: units::Tile          = units::Tile(7);
+static AIR_LABEL_Y: units::Tile          = units::Tile(2);
+static AIR_LABEL_OFS_X: units::HalfTile  = units::HalfTile(14);
+static AIR_LABEL_OFS_Y: units::HalfTile  = units::HalfTile(9);
+static AIR_LABEL_W: units::HalfTile      = units::HalfTile(4);
+static AIR_LABEL_H: units::HalfTile      = units::HalfTile(1);
+static AIR_NUMBER_X: units::Tile         = units::Tile(10);
 
 /// Encapsulates the pysical motion of a player as it relates to
 /// a sprite which can be animated, positioned, and drawn on the screen.
 pub struct Player {
 	// assets
-	sprites:   HashMap<MotionTup, Box<sprite::Updatable<units::Game>>>,
-	hp_sprite: Box<sprite::Drawable<units::Tile>>,
-	hud:       Box<sprite::Updatable<units::Tile>>,
-	hud_fill:  Box<sprite::Updatable<units::HalfTile>>,
+	sprites:    HashMap<MotionTup, Box<sprite::Updatable<units::Game>>>,
+	health_bar: hud::HealthBar,
+	experience_bar: hud::ExperienceBar,
+	weapon_status:  hud::WeaponStatus,
+	air_label:  Box<sprite::Drawable<units::Tile>>,
+	air_number: NumberSprite,
+	air_shown:  i32, // value of `air_number`
+
+	weapons: Vec<Box<weapons::Weapon>>,
+	current_weapon: usize, // index of the weapon being held
 
 	// positioning
 	x: units::Game, 
 	y: units::Game,
+	prev_x: units::Game,
+	prev_y: units::Game,
 	movement:  MotionTup,
 	on_ground: bool,
+	on_platform: bool,
 
 	// physics
 	elapsed_time:  units::Millis,
@@ -100,9 +128,21 @@ pub struct Player {
 	is_interacting:  bool,
 	is_invincible:   bool,
 	is_jump_active:  bool,
+	is_dropping:     bool,
+	is_submerged:    bool,
+
+	air: units::Millis,
+	health:      units::HitPoints,
+	max_health:  units::HitPoints,
 
 	// timers
 	invincible_time: units::Millis,
+	buffered_jump:   units::Millis, // remaining time of the jump buffer
+	coyote_left:     units::Millis, // remaining coyote time
+
+	// forgiveness windows
+	jump_buffer:  units::Millis,
+	coyote_time:  units::Millis,
 }
 
 
@@ -120,34 +160,37 @@ impl Player {
 		let sprite_map = 
 			HashMap::<MotionTup, Box<sprite::Updatable<_>>>::new();
 
-		let health_bar_sprite = Box::new(sprite::Sprite::new(
-			graphics, 
-			(HEALTH_BAR_OFS_X, HEALTH_BAR_OFS_Y),
-			(HEALTH_BAR_W, HEALTH_BAR_H),
-			format!("assets/base/TextBox.bmp"),
-		)) as Box<sprite::Updatable<_>>;
-
-		let health_fill_sprite = Box::new(sprite::Sprite::new(
+		let air_label_sprite = Box::new(sprite::Sprite::new(
 			graphics,
-			(HEALTH_FILL_OFS_X, HEALTH_FILL_OFS_Y),
-			(HEALTH_FILL_W.to_game() - FILL_SHIFT, HEALTH_FILL_H.to_game()),
+			(AIR_LABEL_OFS_X, AIR_LABEL_OFS_Y),
+			(AIR_LABEL_W, AIR_LABEL_H),
 			format!("assets/base/TextBox.bmp"),
-		)) as Box<sprite::Updatable<_>>;
-
-		let digit_3 = Box::new(NumberSprite::new(graphics, 42));
+		)) as Box<sprite::Drawable<_>>;
 
 		// construct new player
 		let mut new_player = Player{
 			elapsed_time: units::Millis(0),
 			sprites:   sprite_map,
-			hud:       health_bar_sprite,
-			hud_fill:  health_fill_sprite,
-			hp_sprite: digit_3,
+			health_bar: hud::HealthBar::new(graphics, MAX_HEALTH, MAX_HEALTH),
+			experience_bar: hud::ExperienceBar::new(graphics),
+			weapon_status:  hud::WeaponStatus::new(graphics),
+			air_label:  air_label_sprite,
+			air_number: NumberSprite::new(graphics, 100),
+			air_shown:  100,
+
+			weapons: vec![
+				Box::new(weapons::PolarStar::new(graphics)) as Box<weapons::Weapon>,
+				Box::new(weapons::MissileLauncher::new(graphics)) as Box<weapons::Weapon>,
+			],
+			current_weapon: 0,
 
 			x: x,
 			y: y,
+			prev_x: x,
+			prev_y: y,
 			movement: (Motion::Standing, Facing::East, Looking::Horizontal),
 			on_ground: false,
+			on_platform: false,
 			
 			velocity_x: units::Velocity(0.0),
 			velocity_y: units::Velocity(0.0),
@@ -156,8 +199,19 @@ impl Player {
 			is_interacting: false,
 			is_jump_active: false,
 			is_invincible:  false,
+			is_dropping:    false,
+			is_submerged:   false,
+
+			air: MAX_AIR,
+			health: MAX_HEALTH,
+			max_health: MAX_HEALTH,
 
 			invincible_time: units::Millis(0),
+			buffered_jump:   units::Millis(0),
+			coyote_left:     units::Millis(0),
+
+			jump_buffer: JUMP_BUFFER,
+			coyote_time: COYOTE_TIME,
 		};
 
 		// load sprites for every possible movement tuple.
@@ -172,12 +226,21 @@ impl Player {
 		new_player
 	}
 
-	/// Draws player to screen
-	pub fn draw(&mut self, display: &mut graphics::Graphics) {
+	/// Draws player to screen, `alpha` of the way between their position
+	/// on the previous tick and their current position.
+	pub fn draw(&mut self, display: &mut graphics::Graphics, alpha: f64) {
+		if self.is_dead() { return; }
+		for weapon in self.weapons.iter_mut() {
+			weapon.draw_projectiles(display, alpha);
+		}
+
 		if self.is_invincible && self.is_strobed() {
 			return;
 		} else {
-			self.sprites.get_mut(&self.movement).unwrap().draw(display, (self.x, self.y));
+			let (x, y) = (self.prev_x.lerp(self.x, alpha), self.prev_y.lerp(self.y, alpha));
+			let (facing, aim) = (self.facing(), self.aim());
+			self.weapons[self.current_weapon].draw_gun(display, x, y, facing, aim);
+			self.sprites.get_mut(&self.movement).unwrap().draw(display, (x, y));
 		}
 	}
 
@@ -186,17 +249,25 @@ impl Player {
 		if self.is_invincible && self.is_strobed() {
 			return;
 		} else {
-			self.hud.draw(display,
-			              (HEALTH_BAR_X,
-			               HEALTH_BAR_Y));
-			
-			self.hud_fill.draw(display,
-			                   (HEALTH_FILL_X,
-			                    HEALTH_FILL_Y));
-			
-			self.hp_sprite.draw(display,
-			                    (units::Tile(3),
-			                     units::Tile(2)));
+			let weapon = &self.weapons[self.current_weapon];
+			self.weapon_status.draw(display, &**weapon);
+			self.experience_bar.draw(display, weapon.experience());
+			self.health_bar.draw(display);
+		}
+
+		// air meter: shown as a percentage, only while submerged
+		if self.is_submerged {
+			let (units::Millis(air), units::Millis(max_air)) = (self.air, MAX_AIR);
+			self.air_label.draw(display, (AIR_LABEL_X, AIR_LABEL_Y));
+
+			// only rebuild the digits when they've changed
+			let percent = ((air * 100) / max_air) as i32;
+			if self.air_shown != percent {
+				self.air_number = NumberSprite::new(display, percent);
+				self.air_shown  = percent;
+			}
+
+			self.air_number.draw(display, (AIR_NUMBER_X, AIR_LABEL_Y));
 		}
 	}
 
@@ -206,6 +277,8 @@ impl Player {
 	pub fn update(&mut self, elapsed_time: units::Millis, map: &map::Map) {
 		// calculate current position
 		self.elapsed_time = elapsed_time;
+		self.prev_x = self.x;
+		self.prev_y = self.y;
 		
 		// update sprite
 		self.current_motion(); // update motion once at beginning of frame for consistency
@@ -217,12 +290,142 @@ impl Player {
 			self.is_invincible = self.invincible_time < DAMAGE_INVINCIBILITY;
 		}
 
+		self.health_bar.update(elapsed_time);
+
+		// hold breath while submerged
+		let (center_x, center_y) = self.center();
+		self.is_submerged = map.is_submerged(center_x, center_y);
+		self.update_air(elapsed_time);
+
 		// run physics sim
 		self.update_x(map);
 		self.update_y(map);
+		self.update_jump_windows(elapsed_time);
+
+		// shots already fired keep flying after switching weapons
+		for weapon in self.weapons.iter_mut() {
+			weapon.update(elapsed_time, map);
+		}
+	}
+
+	/// Fires the player's weapon in the direction they are aiming.
+	pub fn start_fire(&mut self) {
+		let (facing, aim) = (self.facing(), self.aim());
+		self.weapons[self.current_weapon].start_fire(self.x, self.y, facing, aim);
+	}
+
+	/// Switches to the next weapon carried, wrapping around to the first.
+	pub fn next_weapon(&mut self) {
+		self.current_weapon = (self.current_weapon + 1) % self.weapons.len();
+	}
+
+	/// Switches to the previous weapon carried, wrapping around to the last.
+	pub fn previous_weapon(&mut self) {
+		let count = self.weapons.len();
+		self.current_weapon = (self.current_weapon + count - 1) % count;
+	}
+
+	/// Takes up the weapons carried by `other`, along w/ their experience
+	/// & ammo. Any shots `other` still had in flight are lost.
+	pub fn take_weapons(&mut self, other: Player) {
+		self.weapons        = other.weapons;
+		self.current_weapon = other.current_weapon;
+
+		for weapon in self.weapons.iter_mut() {
+			weapon.armament_mut().clear_projectiles();
+		}
+	}
+
+	/// The weapon the player is holding.
+	pub fn weapon(&self) -> &weapons::Weapon {
+		&*self.weapons[self.current_weapon]
+	}
+
+	/// The arms index of each weapon carried, & the position of the one held.
+	pub fn arms(&self) -> (Vec<usize>, usize) {
+		(self.weapons.iter().map(|weapon| weapon.arms_index()).collect(), self.current_weapon)
+	}
+
+	/// Rewards the player's weapon w/ `points` of experience.
+	pub fn gain_experience(&mut self, points: i32) {
+		let current = self.current_weapon;
+		self.gain_weapon_experience(current, points);
+	}
+
+	/// Rewards the weapon carried at `weapon` w/ `points` of experience,
+	/// whether or not it is the one being held.
+	pub fn gain_weapon_experience(&mut self, weapon: usize, points: i32) {
+		self.weapons[weapon].experience_mut().gain(points);
+	}
+
+	pub fn experience(&self) -> &weapons::Experience {
+		self.weapon().experience()
+	}
+
+	/// The shots fired by the player which are still in flight, each
+	/// alongside the position of the weapon which fired it.
+	pub fn projectiles_mut(&mut self) -> Vec<(usize, &mut Projectile)> {
+		self.weapons.iter_mut().enumerate()
+			.flat_map(|(idx, weapon)| {
+				weapon.projectiles_mut().iter_mut().map(move |projectile| (idx, projectile))
+			})
+			.collect()
+	}
+
+	/// Where the player's gun is pointed: a player looking down while on
+	/// the ground is inspecting something, so they aim straight ahead.
+	fn aim(&self) -> Looking {
+		match self.looking() {
+			Looking::Down if self.on_ground() => Looking::Horizontal,
+			looking => looking,
+		}
+	}
+
+	/// Sets how early a jump may be pressed before landing (`jump_buffer`)
+	/// & how late after leaving a ledge (`coyote_time`) it is still performed.
+	/// Either window can be disabled by setting it to zero.
+	pub fn set_jump_windows(&mut self, jump_buffer: units::Millis, coyote_time: units::Millis) {
+		self.jump_buffer = jump_buffer;
+		self.coyote_time = coyote_time;
+	}
+
+	/// Runs down the jump buffer & coyote time, performing a buffered jump
+	/// as soon as the player lands.
+	fn update_jump_windows(&mut self, elapsed_time: units::Millis) {
+		let zero = units::Millis(0);
+
+		if self.on_ground() {
+			self.coyote_left = self.coyote_time;
+		} else if self.coyote_left > zero {
+			self.coyote_left = cmp::max(self.coyote_left - elapsed_time, zero);
+		}
+
+		if self.buffered_jump > zero {
+			if self.on_ground() {
+				self.jump();
+			} else {
+				self.buffered_jump = cmp::max(self.buffered_jump - elapsed_time, zero);
+			}
+		}
+	}
+
+	/// Drains the player's air while they are submerged, once it runs out
+	/// they start taking damage. Air is refilled as soon as they surface.
+	fn update_air(&mut self, elapsed_time: units::Millis) {
+		if !self.is_submerged {
+			self.air = MAX_AIR;
+			return;
+		}
+
+		self.air = cmp::max(self.air - elapsed_time, units::Millis(0));
+		if self.air == units::Millis(0) {
+			self.take_damage(DROWNING_DAMAGE);
+		}
 	}
 
 	fn update_x(&mut self, map: &map::Map) {
+		let max_velocity_x = if self.is_submerged { WATER_MAX_VELOCITY_X } else { MAX_VELOCITY_X };
+
 		// compute next velocity
 		let accel_x: units::Acceleration = if self.accel_x < 0  {
 			if self.on_ground() { -WALKING_ACCEL } else { -AIR_ACCELERATION }
@@ -234,9 +437,9 @@ impl Player {
 
 		// apply maximum bounds to velocity based on situation	
 		if self.accel_x < 0 {
-			self.velocity_x = units::Velocity((*self.velocity_x).max(-*MAX_VELOCITY_X));
+			self.velocity_x = units::Velocity((*self.velocity_x).max(-*max_velocity_x));
 		} else if self.accel_x > 0 {
-			self.velocity_x = units::Velocity((*self.velocity_x).min( *MAX_VELOCITY_X));
+			self.velocity_x = units::Velocity((*self.velocity_x).min( *max_velocity_x));
 		} else if self.on_ground() {
 			let v_friction = FRICTION * self.elapsed_time;
 
@@ -292,17 +495,25 @@ impl Player {
 	}
 
 	fn update_y (&mut self, map: &map::Map) {
+		let was_on_ground = self.on_ground;
+
 		// update velocity
+		let (jump_gravity, gravity, max_velocity_y) = if self.is_submerged {
+			(WATER_JUMP_GRAVITY, WATER_GRAVITY, WATER_MAX_VELOCITY_Y)
+		} else {
+			(JUMP_GRAVITY, GRAVITY, MAX_VELOCITY_Y)
+		};
+
 		let gravity: units::Acceleration = 
 			if self.is_jump_active 
 			&& self.velocity_y < units::Velocity(0.0) {
-				JUMP_GRAVITY
+				jump_gravity
 			} else {
-				GRAVITY
+				gravity
 			};
 
 		let v_gravity = self.velocity_y + (gravity * self.elapsed_time);
-		self.velocity_y = units::Velocity((*v_gravity).min(*MAX_VELOCITY_Y));
+		self.velocity_y = units::Velocity((*v_gravity).min(*max_velocity_y));
 
 		// calculate delta
 		let delta = self.velocity_y * self.elapsed_time;
@@ -310,7 +521,8 @@ impl Player {
 		// check collision in direction of delta
 		if delta > units::Game(0.0) {
 			// react to collision
-			let mut info = self.get_collision_info(&self.bottom_collision(delta), map);
+			let (mut info, on_platform) = self.get_landing_info(&self.bottom_collision(delta), map);
+			self.on_platform = on_platform;
 			self.y = if info.collided {
 				self.velocity_y = units::Velocity(0.0);
 				self.on_ground = true;
@@ -330,6 +542,7 @@ impl Player {
 
 		} else {
 			// react to collision
+			self.on_platform = false;
 			let mut info = self.get_collision_info(&self.top_collision(delta), map);
 			self.y = if info.collided {
 				self.velocity_y = units::Velocity(0.0);
@@ -347,6 +560,39 @@ impl Player {
 				self.y
 			};
 		}
+
+		self.collide_slopes(map, was_on_ground);
+		self.is_dropping = false;
+	}
+
+	/// Rests the player's feet upon, and keeps their head below, any slopes.
+	///
+	/// Slopes are only tested beneath the center of the player. While walking
+	/// down an incline the player is pulled back onto its surface, so that
+	/// they follow it smoothly rather than falling down it in steps.
+	fn collide_slopes(&mut self, map: &map::Map, was_on_ground: bool) {
+		let center_x = self.x + Y_BOX.left() + (Y_BOX.width() / units::Game(2.0));
+		let middle   = self.y + Y_BOX.top() + (Y_BOX.height() / units::Game(2.0));
+		let top      = self.y + Y_BOX.top();
+		let bottom   = self.y + Y_BOX.bottom();
+
+		if self.velocity_y >= units::Velocity(0.0) {
+			let reach = if was_on_ground { bottom + SLOPE_SNAP } else { bottom };
+			if let Some(surface) = map.floor_slope_at(center_x, middle, reach) {
+				self.velocity_y  = units::Velocity(0.0);
+				self.on_ground   = true;
+				self.on_platform = false;
+				self.y = surface - Y_BOX.bottom();
+			}
+		}
+
+		if let Some(surface) = map.ceiling_slope_at(center_x, top, middle) {
+			if self.velocity_y < units::Velocity(0.0) {
+				self.velocity_y = units::Velocity(0.0);
+			}
+
+			self.y = surface - Y_BOX.top();
+		}
 	}
 
 	fn get_collision_info(&self, hitbox: &Rectangle, tile_map: &map::Map) -> Info {
@@ -364,6 +610,27 @@ impl Player {
 		info
 	}
 
+	/// Collides a player moving downwards w/ the map.
+	///
+	/// Platforms are only solid if the player's feet were above them to begin
+	/// with, unless the player is dropping through them. Also reports whether
+	/// the player landed upon a platform.
+	fn get_landing_info(&self, hitbox: &Rectangle, tile_map: &map::Map) -> (Info, bool) {
+		let info = self.get_collision_info(hitbox, tile_map);
+		if info.collided || self.is_dropping {
+			return (info, false);
+		}
+
+		let feet = self.y + Y_BOX.bottom();
+		for tile in tile_map.get_colliding_tiles(hitbox).iter() {
+			if tile.tile_type == TileType::Platform && feet <= tile.row.to_game() {
+				return (Info {collided: true, row: tile.row, col: tile.col}, true);
+			}
+		}
+
+		(info, false)
+	}
+
 	/// This updates the `self.movement` tuple
 	/// The `Motion` is kept as-is, but the `Facing` portion of the tuple
 	/// is replaced with `direction`.
@@ -508,15 +775,34 @@ impl Player {
 	///
 	/// The effects of a jump against gravity are `instantaneous` and do not
 	/// consider acceleration.
+	///
+	/// A player looking down while standing on a platform will instead
+	/// drop through it.
 	pub fn start_jump(&mut self) {
+		if self.on_platform && self.looking() == Looking::Down {
+			self.is_dropping = true;
+			self.is_interacting = false;
+			self.coyote_left = units::Millis(0);
+			return;
+		}
+
 		self.is_jump_active = true;
 		self.is_interacting = false;
 
-		if self.on_ground() {
-			self.velocity_y = -JUMP_SPEED;
+		if self.on_ground() || self.coyote_left > units::Millis(0) {
+			self.jump();
+		} else {
+			self.buffered_jump = self.jump_buffer;
 		}
 	}
 
+	/// Launches the player upwards, using up any buffered jump or coyote time.
+	fn jump(&mut self) {
+		self.velocity_y = if self.is_submerged { -WATER_JUMP_SPEED } else { -JUMP_SPEED };
+		self.buffered_jump = units::Millis(0);
+		self.coyote_left   = units::Millis(0);
+	}
+
 	/// A player will immediately cease their jump and become subject
 	/// to the effects of gravity.
 	///
@@ -565,16 +851,29 @@ impl Player {
 		}
 	}
 
-	/// The player takes damage from the world
-	pub fn take_damage(&mut self) {
-		if self.is_invincible { return; }
+	/// The player takes `damage` from the world, then becomes invincible
+	/// for a short while.
+	pub fn take_damage(&mut self, damage: units::HitPoints) {
+		if self.is_invincible || self.is_dead() { return; }
 
 		self.velocity_y = units::Velocity((*self.velocity_y).min(-*SHORT_JUMP_SPEED));
 
 		self.is_invincible    = true;
 		self.invincible_time  = units::Millis(0);
 
-		println!("bat has collided with me! D:");
+		self.health = cmp::max(self.health - damage, 0);
+		self.health_bar.set_health(self.health);
+		self.weapons[self.current_weapon].experience_mut().lose(damage * EXPERIENCE_LOSS);
+	}
+
+	/// A player w/ no health left.
+	pub fn is_dead(&self) -> bool {
+		self.health <= 0
+	}
+
+	/// The player's current & maximum health.
+	pub fn health(&self) -> (units::HitPoints, units::HitPoints) {
+		(self.health, self.max_health)
 	}
 
 	/// Returns true if the player is currently invisible due to an
@@ -589,10 +888,29 @@ impl Player {
 		(*invincible_time / *flash_time) % 2 == 0
 	}
 
+	/// The top-left corner of the player's sprite.
+	pub fn position(&self) -> (units::Game, units::Game) {
+		(self.x, self.y)
+	}
+
 	pub fn center_x(&self) -> units::Game {
 		self.x + units::HalfTile(1)
 	}
 
+	pub fn center(&self) -> (units::Game, units::Game) {
+		(self.x + units::HalfTile(1), self.y + units::HalfTile(1))
+	}
+
+	pub fn facing(&self) -> Facing {
+		let (_, facing, _) = self.movement;
+		facing
+	}
+
+	pub fn looking(&self) -> Looking {
+		let (_, _, looking) = self.movement;
+		looking
+	}
+
 	// x-axis collision detection
 	fn left_collision(&self, delta: units::Game) -> Rectangle {
 		assert!(delta <= units::Game(0.0));
@@ -646,3 +964,303 @@ impl Player {
 		self.on_ground
 	}
 }
+
+#[cfg(test)]
+fn simulate(player: &mut Player, map: &map::Map, ticks: usize) -> Vec<(units::Game, units::Game)> {
+	use game;
+
+	(0..ticks).map(|tick| {
+		player.update(game::timestep(tick as u64), map);
+		(player.x, player.y)
+	}).collect()
+}
+
+#[test]
+fn test_player_lands_on_floor() {
+	let mut display = graphics::Graphics::headless();
+	let map = map::Map::create_test_map(&mut display);
+	let mut quote = Player::new(&mut display, units::Tile(10).to_game(), units::Tile(7).to_game());
+
+	simulate(&mut quote, &map, 120);
+	assert!(quote.on_ground());
+	assert!(quote.y == units::Tile(13).to_game());
+}
+
+#[test]
+fn test_jump_is_reproducible() {
+	let mut display = graphics::Graphics::headless();
+	let map = map::Map::create_test_map(&mut display);
+
+	let mut trajectories = (0..2).map(|_| {
+		let mut quote = Player::new(&mut display, units::Tile(10).to_game(), units::Tile(13).to_game());
+		simulate(&mut quote, &map, 10);
+
+		quote.start_moving_right();
+		quote.start_jump();
+		simulate(&mut quote, &map, 90)
+	}).collect::<Vec<_>>();
+
+	let (second, first) = (trajectories.pop().unwrap(), trajectories.pop().unwrap());
+	assert!(first == second);
+
+	// the player leaves the ground & lands again within the sampled window
+	let apex = first.iter().fold(units::Tile(13).to_game(), |apex, &(_, y)| {
+		if y < apex { y } else { apex }
+	});
+	assert!(apex < units::Tile(12).to_game());
+	assert!(first[first.len() - 1].1 == units::Tile(13).to_game());
+}
+
+#[test]
+fn test_player_walks_up_slope() {
+	use game;
+
+	let map_file = map::loader::parse("
+		backdrop assets/base/bkBlue.bmp
+		tileset  assets/base/Stage/PrtCave.bmp
+		size     10 4
+
+		[collision]
+		#........#
+		#........#
+		#....67###
+		##########
+	").unwrap();
+
+	let mut display = graphics::Graphics::headless();
+	let map = map::Map::from_file(&mut display, &map_file);
+	let mut quote = Player::new(&mut display, units::Tile(1).to_game(), units::Tile(1).to_game());
+	simulate(&mut quote, &map, 30);
+
+	quote.start_moving_right();
+	let path: Vec<_> = (0..120).map(|tick| {
+		quote.update(game::timestep(tick), &map);
+		(quote.y, quote.on_ground())
+	}).collect();
+
+	// kept on the incline, never lifted by more than it over a single tick
+	for step in path.windows(2) {
+		let ((from, _), (to, grounded)) = (step[0], step[1]);
+		assert!(from - to <= units::Game(2.0));
+		assert!(grounded);
+	}
+
+	assert!(quote.on_ground());
+	assert!(quote.y == units::Tile(1).to_game());
+}
+
+#[test]
+fn test_player_leaves_an_open_map() {
+	let map_file = map::loader::parse("
+		backdrop assets/base/bkBlue.bmp
+		tileset  assets/base/Stage/PrtCave.bmp
+		size     4 3
+
+		[collision]
+		....
+		....
+		####
+	").unwrap();
+
+	let mut display = graphics::Graphics::headless();
+	let map = map::Map::from_file(&mut display, &map_file);
+
+	// walk off either edge, then fall away below the map
+	let mut east = Player::new(&mut display, units::Tile(1).to_game(), units::Tile(1).to_game());
+	east.start_moving_right();
+	simulate(&mut east, &map, 240);
+	assert!(east.x > map.width() && east.y > map.height());
+
+	let mut west = Player::new(&mut display, units::Tile(2).to_game(), units::Tile(1).to_game());
+	west.start_moving_left();
+	simulate(&mut west, &map, 240);
+	assert!(west.x < units::Game(0.0) && west.y > map.height());
+}
+
+#[test]
+fn test_player_drops_through_platform() {
+	let map_file = map::loader::parse("
+		backdrop assets/base/bkBlue.bmp
+		tileset  assets/base/Stage/PrtCave.bmp
+		size     5 8
+
+		[collision]
+		#...#
+		#...#
+		#...#
+		#...#
+		#---#
+		#...#
+		#...#
+		#####
+	").unwrap();
+
+	let mut display = graphics::Graphics::headless();
+	let map = map::Map::from_file(&mut display, &map_file);
+
+	// jump up through the platform & land on top of it
+	let mut quote = Player::new(&mut display, units::Tile(2).to_game(), units::Tile(6).to_game());
+	simulate(&mut quote, &map, 10);
+	quote.start_jump();
+	simulate(&mut quote, &map, 120);
+	assert!(quote.on_ground());
+	assert!(quote.y == units::Tile(3).to_game());
+
+	// then drop back down through it
+	quote.stop_moving();
+	simulate(&mut quote, &map, 1);
+	quote.look_down();
+	quote.start_jump();
+	simulate(&mut quote, &map, 120);
+	assert!(quote.on_ground());
+	assert!(quote.y == units::Tile(6).to_game());
+}
+
+#[test]
+fn test_jump_pressed_before_landing_is_buffered() {
+	let mut display = graphics::Graphics::headless();
+	let map = map::Map::create_test_map(&mut display);
+
+	for &(jump_buffer, jumps) in [(JUMP_BUFFER, true), (units::Millis(0), false)].iter() {
+		let mut quote = Player::new(&mut display, units::Tile(10).to_game(), units::Tile(11).to_game());
+		quote.set_jump_windows(jump_buffer, COYOTE_TIME);
+		quote.stop_moving();
+
+		// press jump a few frames before touching down
+		let frames = simulate(&mut quote, &map, 120);
+		let landing = frames.iter().position(|&(_, y)| y == units::Tile(13).to_game()).unwrap();
+
+		let mut quote = Player::new(&mut display, units::Tile(10).to_game(), units::Tile(11).to_game());
+		quote.set_jump_windows(jump_buffer, COYOTE_TIME);
+		quote.stop_moving();
+		simulate(&mut quote, &map, landing - 3);
+		quote.start_jump();
+
+		// once landed, the player leaves the floor again only if the jump was buffered
+		let frames = simulate(&mut quote, &map, 10);
+		assert_eq!(frames[3..].iter().any(|&(_, y)| y < units::Tile(13).to_game()), jumps);
+	}
+}
+
+#[test]
+fn test_jump_just_after_leaving_ledge() {
+	let map_file = map::loader::parse("
+		backdrop assets/base/bkBlue.bmp
+		tileset  assets/base/Stage/PrtCave.bmp
+		size     8 8
+
+		[collision]
+		#......#
+		#......#
+		#......#
+		#......#
+		#......#
+		###....#
+		#......#
+		########
+	").unwrap();
+
+	let mut display = graphics::Graphics::headless();
+	let map = map::Map::from_file(&mut display, &map_file);
+
+	for &(coyote_time, jumps) in [(COYOTE_TIME, true), (units::Millis(0), false)].iter() {
+		let mut quote = Player::new(&mut display, units::Tile(1).to_game(), units::Tile(4).to_game());
+		quote.set_jump_windows(JUMP_BUFFER, coyote_time);
+		quote.start_moving_right();
+
+		// walk off the ledge, then press jump a couple of frames late
+		let mut ticks = 0;
+		while quote.on_ground() || ticks == 0 {
+			simulate(&mut quote, &map, 1);
+			ticks += 1;
+			assert!(ticks < 120);
+		}
+
+		simulate(&mut quote, &map, 2);
+		quote.start_jump();
+		simulate(&mut quote, &map, 1);
+		assert_eq!(quote.velocity_y < units::Velocity(0.0), jumps);
+	}
+}
+
+#[test]
+fn test_player_sinks_slowly_and_drowns() {
+	let map_file = map::loader::parse("
+		backdrop assets/base/bkBlue.bmp
+		tileset  assets/base/Stage/PrtCave.bmp
+		size     3 40
+		water    10
+	").unwrap();
+
+	let mut display = graphics::Graphics::headless();
+	let flooded = map::Map::from_file(&mut display, &map_file);
+	let mut dry = map::Map::from_file(&mut display, &map_file);
+	dry.set_water_level(None);
+
+	let mut swimmer = Player::new(&mut display, units::Tile(1).to_game(), units::Tile(12).to_game());
+	let mut faller  = Player::new(&mut display, units::Tile(1).to_game(), units::Tile(12).to_game());
+	swimmer.stop_moving();
+	faller.stop_moving();
+
+	simulate(&mut swimmer, &flooded, 30);
+	simulate(&mut faller, &dry, 30);
+	assert!(swimmer.y < faller.y);
+	assert!(!swimmer.is_invincible);
+
+	// keep the swimmer afloat until they run out of air
+	let units::Millis(air) = MAX_AIR;
+	for _ in 0..(air / 16) {
+		swimmer.y = units::Tile(12).to_game();
+		simulate(&mut swimmer, &flooded, 1);
+	}
+
+	assert!(swimmer.is_invincible);
+}
+
+#[test]
+fn test_damage_depletes_health() {
+	let mut display = graphics::Graphics::headless();
+	let mut quote = Player::new(&mut display, units::Game(0.0), units::Game(0.0));
+
+	quote.take_damage(1);
+	quote.take_damage(1); // ignored while invincible
+	assert!(quote.health() == (MAX_HEALTH - 1, MAX_HEALTH));
+	assert!(!quote.is_dead());
+
+	quote.is_invincible = false;
+	quote.take_damage(10);
+	assert!(quote.health() == (0, MAX_HEALTH));
+	assert!(quote.is_dead());
+}
+
+#[test]
+fn test_damage_costs_experience() {
+	let mut display = graphics::Graphics::headless();
+	let mut quote = Player::new(&mut display, units::Game(0.0), units::Game(0.0));
+
+	quote.gain_experience(11);
+	assert_eq!(quote.experience().level(), 2);
+
+	quote.take_damage(1);
+	assert_eq!(quote.experience().level(), 1);
+	assert_eq!(quote.experience().progress().0, 11 - EXPERIENCE_LOSS);
+}
+
+#[test]
+fn test_cycle_weapons() {
+	let mut display = graphics::Graphics::headless();
+	let mut quote = Player::new(&mut display, units::Game(0.0), units::Game(0.0));
+	assert_eq!(quote.weapon().ammo(), None);
+
+	quote.previous_weapon();
+	let (_, max) = quote.weapon().ammo().unwrap();
+
+	// one missile in flight at a time, each costing ammunition
+	quote.start_fire();
+	quote.start_fire();
+	assert_eq!(quote.weapon().ammo(), Some((max - 1, max)));
+
+	quote.next_weapon();
+	assert_eq!(quote.weapon().ammo(), None);
+	assert_eq!(quote.projectiles_mut().len(), 1);
+}
@@ -0,0 +1,36 @@
+use graphics;
+use hud;
+use input::{self, Action};
+use scenes::{Scene, Transition};
+
+/// Asks whether the player would like to retry from their last checkpoint.
+pub struct GameOver {
+	prompt:  hud::YesNoPrompt,
+	retry:   bool,
+}
+
+impl GameOver {
+	pub fn new(display: &mut graphics::Graphics) -> GameOver {
+		GameOver { prompt: hud::YesNoPrompt::new(display), retry: true }
+	}
+}
+
+impl Scene for GameOver {
+	/// "Yes" returns to the world, which then respawns the player.
+	/// "No" quits the game.
+	fn handle_input(&mut self, controller: &input::Input,
+	                _display: &mut graphics::Graphics) -> Transition {
+		if controller.was_pressed(Action::MoveLeft)
+		|| controller.was_pressed(Action::MoveRight) {
+			self.retry = !self.retry;
+		} else if controller.was_pressed(Action::Jump) {
+			return if self.retry { Transition::Pop } else { Transition::Quit };
+		}
+
+		Transition::None
+	}
+
+	fn draw(&mut self, display: &mut graphics::Graphics, _alpha: f64) {
+		self.prompt.draw(display, self.retry);
+	}
+}
@@ -0,0 +1,550 @@
+use std::path::Path;
+
+use camera;
+use enemies;
+use game;
+use graphics;
+use input::{self, Action};
+use map;
+use particles;
+use player;
+use scenes::{self, Scene, Transition};
+use units;
+use units::AsPixel;
+
+use sdl2::pixels::Color;
+use sdl2::rect::Rect;
+
+/// How long the player's remains linger before the screen starts to fade.
+static DEATH_DELAY: units::Millis  = units::Millis(500);
+static FADE_TIME:   units::Millis  = units::Millis(1000);
+static DEATH_PUFFS: usize          = 8;
+static ENEMY_DEATH_PUFFS: usize    = 6;
+
+/// Experience earned by the player's weapon for each shot which lands.
+static HIT_EXPERIENCE: i32 = 1;
+
+/// What is happening to the player.
+#[derive(Clone,Copy,PartialEq)]
+enum Phase {
+	Playing,
+	/// The player has died: the world runs on while the screen fades out.
+	Dying(units::Millis),
+	/// Waiting on the game over screen.
+	Dead,
+}
+
+/// The world itself: a map, the player and their enemies.
+pub struct Gameplay {
+	quote:   player::Player,
+	enemies: Vec<Box<enemies::Enemy>>,
+	spawns:  Vec<Spawn>,
+	map:     map::Map,
+	camera:  camera::Camera,
+
+	particles:   particles::ParticleSystem,
+	phase:       Phase,
+	checkpoint:  (units::Game, units::Game),
+}
+
+impl Gameplay {
+	/// The demo map, w/ the player at its start.
+	pub fn new(display: &mut graphics::Graphics) -> Gameplay {
+		let map = map::Map::create_test_map(display);
+		let (start_x, start_y) = map.start();
+		let spawns = find_spawns(&map).unwrap();
+
+		let mut gameplay = Gameplay {
+			quote:   player::Player::new(display, start_x, start_y),
+			enemies: spawn_enemies(display, &spawns),
+			spawns:  spawns,
+			map:     map,
+
+			camera: camera::Camera::new(camera::dead_zone(), camera::LOOK_AHEAD),
+
+			particles:  particles::ParticleSystem::new(),
+			phase:      Phase::Playing,
+			checkpoint: (start_x, start_y),
+		};
+
+		gameplay.camera.center_on(&gameplay.quote, &gameplay.map);
+		gameplay
+	}
+
+	/// Replaces the current map w/ the map file found at `path`.
+	pub fn load_map(&mut self, display: &mut graphics::Graphics, path: &Path) -> Result<(), String> {
+		let map = try!(map::Map::load(display, path));
+		self.enter(display, map)
+	}
+
+	/// Replaces the current map w/ an original Cave Story stage.
+	///
+	/// Following Cave Story's conventions: the tileset is expected to sit
+	/// next to its attributes, i.e: `PrtCave.pxa` is drawn w/ `PrtCave.bmp`
+	pub fn load_stage(&mut self, display: &mut graphics::Graphics,
+	                  pxm_path: &Path, pxa_path: &Path) -> Result<(), String> {
+		let tileset = format!("{}", pxa_path.with_extension("bmp").display());
+		let map = try!(map::Map::load_stage(
+			display, pxm_path, pxa_path,
+			tileset, format!("assets/base/bkBlue.bmp")
+		));
+
+		self.enter(display, map)
+	}
+
+	/// Starts the player over at the start of `map`, amongst its enemies.
+	fn enter(&mut self, display: &mut graphics::Graphics, map: map::Map) -> Result<(), String> {
+		// fail before anything is replaced
+		let spawns = try!(find_spawns(&map));
+
+		self.map    = map;
+		self.spawns = spawns;
+		self.checkpoint = self.map.start();
+		self.respawn(display);
+		Ok(())
+	}
+
+	pub fn player(&self) -> &player::Player {
+		&self.quote
+	}
+
+	/// Translates the state of the controller into player actions.
+	fn handle_player_input(&mut self, controller: &input::Input) {
+		// Handle player movement
+		if controller.is_held(Action::MoveLeft)
+			&& controller.is_held(Action::MoveRight) {
+
+			self.quote.stop_moving();
+		} else if controller.is_held(Action::MoveLeft) {
+			self.quote.start_moving_left();
+		} else if controller.is_held(Action::MoveRight) {
+			self.quote.start_moving_right();
+		} else {
+			self.quote.stop_moving();
+		}
+
+		// Handle player looking
+		if controller.is_held(Action::LookUp)
+			&& controller.is_held(Action::LookDown) {
+
+			self.quote.look_horizontal();
+		} else if controller.is_held(Action::LookUp) {
+			self.quote.look_up();
+		} else if controller.is_held(Action::LookDown) {
+			self.quote.look_down();
+		} else {
+			self.quote.look_horizontal();
+		}
+
+		// Handle player jump
+		if controller.was_pressed(Action::Jump) {
+			self.quote.start_jump();
+		} else if controller.was_released(Action::Jump) {
+			self.quote.stop_jump();
+		}
+
+		// Handle player fire
+		if controller.was_pressed(Action::Fire) {
+			self.quote.start_fire();
+		}
+
+		// Handle weapon switching
+		if controller.was_pressed(Action::NextWeapon) {
+			self.quote.next_weapon();
+		} else if controller.was_pressed(Action::PreviousWeapon) {
+			self.quote.previous_weapon();
+		}
+	}
+
+	/// Covers the screen in black, growing more opaque as the fade progresses.
+	fn draw_fade(&mut self, display: &mut graphics::Graphics, elapsed: units::Millis) {
+		if elapsed < DEATH_DELAY { return; }
+
+		let (units::Millis(elapsed), units::Millis(fade)) = (elapsed - DEATH_DELAY, FADE_TIME);
+		let opacity = (elapsed as f64 / fade as f64).min(1.0);
+
+		let (units::Pixel(w), units::Pixel(h)) =
+			(game::SCREEN_WIDTH.to_pixel(), game::SCREEN_HEIGHT.to_pixel());
+		display.fill_rect(&Rect::new(0, 0, w as u32, h as u32),
+		                  Color::RGBA(0, 0, 0, (opacity * 255.0) as u8));
+	}
+
+	/// Advances the world while the player's remains fade away, then
+	/// shows the game over screen.
+	fn update_dying(&mut self, elapsed_time: units::Millis,
+	                display: &mut graphics::Graphics, elapsed: units::Millis) -> Transition {
+		self.map.update(elapsed_time);
+		self.update_enemies(elapsed_time);
+		self.particles.update(elapsed_time);
+
+		let elapsed = elapsed + elapsed_time;
+		if elapsed >= DEATH_DELAY + FADE_TIME {
+			self.phase = Phase::Dead;
+			Transition::Push(Box::new(scenes::GameOver::new(display)))
+		} else {
+			self.phase = Phase::Dying(elapsed);
+			Transition::None
+		}
+	}
+
+	/// Called once, as soon as the player has run out of health.
+	/// Their sprite bursts into smoke & the screen fades out.
+	fn on_player_death(&mut self, display: &mut graphics::Graphics) {
+		let (x, y) = self.quote.center();
+		for puff in particles::Smoke::burst(display, x, y, DEATH_PUFFS) {
+			self.particles.add(puff);
+		}
+
+		self.phase = Phase::Dying(units::Millis(0));
+	}
+
+	fn update_enemies(&mut self, elapsed_time: units::Millis) {
+		for enemy in self.enemies.iter_mut() {
+			enemy.update(elapsed_time, &self.map, &self.quote);
+		}
+	}
+
+	/// Lands each of the player's shots on the first living enemy it touches,
+	/// showing the damage dealt above it. Experience goes to the weapon
+	/// which fired the shot, even if the player has since switched.
+	fn collide_projectiles(&mut self, display: &mut graphics::Graphics) {
+		let mut hits = Vec::new();
+		for (weapon, projectile) in self.quote.projectiles_mut() {
+			let rectangle = projectile.collision_rectangle();
+			if let Some(enemy) = self.enemies.iter_mut()
+				.find(|enemy| !enemy.is_dead() && enemy.collision_rectangle().collides_with(&rectangle)) {
+
+				let (damage, target) = (projectile.damage(), enemy.collision_rectangle());
+				enemy.take_damage(damage);
+				projectile.collide_with_enemy();
+				hits.push(weapon);
+
+				let center_x = target.x + target.width / units::Game(2.0);
+				self.particles.add(Box::new(
+					particles::DamageNumber::new(display, damage, center_x, target.y)
+				));
+			}
+		}
+
+		for weapon in hits {
+			self.quote.gain_weapon_experience(weapon, HIT_EXPERIENCE);
+		}
+
+		self.remove_dead_enemies(display);
+	}
+
+	/// Enemies which have been killed go up in smoke.
+	fn remove_dead_enemies(&mut self, display: &mut graphics::Graphics) {
+		let enemies = ::std::mem::replace(&mut self.enemies, Vec::new());
+		let (dead, alive): (Vec<_>, Vec<_>) =
+			enemies.into_iter().partition(|enemy| enemy.is_dead());
+		self.enemies = alive;
+
+		for enemy in dead {
+			let rectangle = enemy.collision_rectangle();
+			let (x, y) = (rectangle.x + rectangle.width / units::Game(2.0),
+			              rectangle.y + rectangle.height / units::Game(2.0));
+
+			for puff in particles::Smoke::burst(display, x, y, ENEMY_DEATH_PUFFS) {
+				self.particles.add(puff);
+			}
+		}
+	}
+
+	/// Puts a fresh player at the last checkpoint & resets the enemies.
+	/// The player keeps the weapons they carried, w/ their experience & ammo.
+	fn respawn(&mut self, display: &mut graphics::Graphics) {
+		let (x, y) = self.checkpoint;
+		let fallen = ::std::mem::replace(&mut self.quote, player::Player::new(display, x, y));
+		self.quote.take_weapons(fallen);
+		self.enemies = spawn_enemies(display, &self.spawns);
+
+		self.particles.clear();
+		self.camera.center_on(&self.quote, &self.map);
+		self.phase = Phase::Playing;
+	}
+}
+
+impl Scene for Gameplay {
+	fn handle_input(&mut self, controller: &input::Input,
+	                display: &mut graphics::Graphics) -> Transition {
+		if self.phase != Phase::Playing {
+			return Transition::None;
+		}
+
+		if controller.was_pressed(Action::Pause) {
+			return Transition::Push(Box::new(scenes::Pause::new()));
+		} else if controller.was_pressed(Action::Inventory) {
+			let (arms, current) = self.quote.arms();
+			return Transition::Push(Box::new(scenes::Inventory::new(display, &arms, current)));
+		}
+
+		self.handle_player_input(controller);
+		Transition::None
+	}
+
+	/// Passes the current time in milliseconds to our underlying actors.
+	fn update(&mut self, elapsed_time: units::Millis,
+	          display: &mut graphics::Graphics) -> Transition {
+		match self.phase {
+			Phase::Playing => {},
+			Phase::Dying(elapsed) => return self.update_dying(elapsed_time, display, elapsed),
+			Phase::Dead => return Transition::None,
+		}
+
+		self.map.update(elapsed_time);
+		self.quote.update(elapsed_time, &self.map);
+		self.update_enemies(elapsed_time);
+		self.particles.update(elapsed_time);
+		self.camera.update(elapsed_time, &self.quote, &self.map);
+
+		if let Some(save_point) = self.map.save_point_at(&self.quote.damage_rectangle()) {
+			self.checkpoint = save_point;
+		}
+
+		self.collide_projectiles(display);
+
+		let player = self.quote.damage_rectangle();
+		let contact = self.enemies.iter()
+			.find(|enemy| enemy.damage_rectangle().collides_with(&player))
+			.map(|enemy| enemy.contact_damage());
+
+		if let Some(damage) = contact {
+			self.quote.take_damage(damage);
+		}
+
+		if let Some(damage) = self.map.hazard_damage(&self.quote.damage_rectangle()) {
+			self.quote.take_damage(damage);
+		}
+
+		if self.quote.is_dead() {
+			self.on_player_death(display);
+		}
+
+		Transition::None
+	}
+
+	/// Instructs our actors to draw their current state to the screen,
+	/// moving actors are drawn interpolated by `alpha`.
+	fn draw(&mut self, display: &mut graphics::Graphics, alpha: f64) {
+		// background
+		let (camera_x, camera_y) = self.camera.position(alpha);
+		display.set_camera(camera_x, camera_y);
+
+		self.map.draw_background(display);
+		self.map.draw_sprites(display);
+
+		// foreground
+		self.quote.draw(display, alpha);
+		for enemy in self.enemies.iter_mut() {
+			enemy.draw(display, alpha);
+		}
+		self.particles.draw(display);
+		self.map.draw(display);
+
+		// ui
+		display.reset_camera();
+		self.quote.draw_hud(display);
+
+		if let Phase::Dying(elapsed) = self.phase {
+			self.draw_fade(display, elapsed);
+		}
+	}
+
+	/// Returning from the game over screen means the player chose to retry.
+	fn resume(&mut self, display: &mut graphics::Graphics) {
+		if self.phase == Phase::Dead {
+			self.respawn(display);
+		}
+	}
+
+	fn as_gameplay(&mut self) -> Option<&mut Gameplay> { Some(self) }
+}
+
+/// The kind of an enemy placed on the map, & where it starts.
+type Spawn = (enemies::Kind, units::Game, units::Game);
+
+/// Every enemy placed on `map`, failing on the first of an unknown kind.
+fn find_spawns(map: &map::Map) -> Result<Vec<Spawn>, String> {
+	map.enemies().into_iter().map(|(kind, x, y)| {
+		enemies::Kind::from_name(kind)
+			.map(|kind| (kind, x, y))
+			.ok_or(format!("unknown enemy `{}`", kind))
+	}).collect()
+}
+
+/// Fresh enemies in their starting positions.
+fn spawn_enemies(display: &mut graphics::Graphics, spawns: &[Spawn]) -> Vec<Box<enemies::Enemy>> {
+	spawns.iter().map(|&(kind, x, y)| kind.spawn(display, x, y)).collect()
+}
+
+#[test]
+fn test_death_leads_to_game_over() {
+	use units::AsGame;
+
+	let mut display = graphics::Graphics::headless();
+	let mut gameplay = Gameplay::new(&mut display);
+	gameplay.checkpoint = (units::Tile(4).to_game(), units::Tile(10).to_game());
+
+	gameplay.quote.take_damage(100);
+	gameplay.update(game::timestep(0), &mut display);
+	assert!(gameplay.phase == Phase::Dying(units::Millis(0)));
+
+	// the screen fades out, then asks whether to retry
+	let mut ticks = 0;
+	loop {
+		ticks += 1;
+		match gameplay.update(game::timestep(ticks), &mut display) {
+			Transition::Push(_) => break,
+			_ => assert!(ticks < 1000),
+		}
+	}
+
+	gameplay.resume(&mut display);
+	assert!(gameplay.phase == Phase::Playing);
+	assert!(!gameplay.quote.is_dead());
+	assert!(gameplay.quote.position() == gameplay.checkpoint);
+}
+
+#[test]
+fn test_respawning_keeps_weapons() {
+	let mut display = graphics::Graphics::headless();
+	let mut gameplay = Gameplay::new(&mut display);
+
+	gameplay.quote.gain_experience(5);
+	gameplay.quote.previous_weapon();
+	gameplay.quote.start_fire();
+	let ammo = gameplay.quote.weapon().ammo();
+
+	gameplay.quote.take_damage(100);
+	gameplay.respawn(&mut display);
+
+	// still holding the launcher, short of the missile fired before dying
+	assert_eq!(gameplay.quote.weapon().ammo(), ammo);
+	assert!(gameplay.quote.projectiles_mut().is_empty());
+	gameplay.quote.next_weapon();
+	assert_eq!(gameplay.quote.experience().progress().0, 5);
+}
+
+#[test]
+fn test_maps_place_any_number_of_enemies() {
+	let mut display = graphics::Graphics::headless();
+	let mut gameplay = Gameplay::new(&mut display);
+	assert_eq!(gameplay.enemies.len(), 1);
+
+	let map = |text: &str, display: &mut graphics::Graphics| {
+		map::Map::from_file(display, &map::loader::parse(text).unwrap())
+	};
+
+	let header = "backdrop bk.bmp\ntileset t.bmp\nsize 20 15\n";
+	let bats = map(&format!("{}enemy bat 2 2\nenemy bat 8 2\nenemy bat 14 2", header), &mut display);
+	assert!(gameplay.enter(&mut display, bats).is_ok());
+	assert_eq!(gameplay.enemies.len(), 3);
+
+	// an unknown kind leaves the current map in place
+	let ghosts = map(&format!("{}enemy ghost 2 2", header), &mut display);
+	assert_eq!(gameplay.enter(&mut display, ghosts).err().unwrap(), "unknown enemy `ghost`");
+	assert_eq!(gameplay.enemies.len(), 3);
+	// as do its enemies once the player respawns
+	gameplay.enemies.clear();
+	gameplay.respawn(&mut display);
+	assert_eq!(gameplay.enemies.len(), 3);
+}
+
+#[test]
+fn test_killed_enemies_go_up_in_smoke() {
+	let mut display = graphics::Graphics::headless();
+	let mut gameplay = Gameplay::new(&mut display);
+	gameplay.enemies[0].take_damage(1);
+	gameplay.remove_dead_enemies(&mut display);
+	assert_eq!(gameplay.enemies.len(), 1);
+	assert!(gameplay.particles.is_empty());
+
+	gameplay.enemies[0].take_damage(100);
+	gameplay.remove_dead_enemies(&mut display);
+	assert!(gameplay.enemies.is_empty());
+	assert!(!gameplay.particles.is_empty());
+}
+
+#[test]
+fn test_hits_earn_experience() {
+	let mut display = graphics::Graphics::headless();
+	let mut gameplay = Gameplay::new(&mut display);
+
+	// a shot fired from on top of the bat cannot miss it
+	let target = gameplay.enemies[0].collision_rectangle();
+	gameplay.quote = player::Player::new(&mut display, target.x, target.y);
+	gameplay.quote.start_fire();
+	gameplay.collide_projectiles(&mut display);
+
+	assert!(gameplay.quote.projectiles_mut().iter().all(|&(_, ref projectile)| !projectile.is_alive()));
+	assert_eq!(gameplay.quote.experience().progress().0, HIT_EXPERIENCE);
+}
+
+#[test]
+fn test_shots_pass_through_enemies_killed_in_the_same_tick() {
+	let mut display = graphics::Graphics::headless();
+	let mut gameplay = Gameplay::new(&mut display);
+
+	// a cave bat (of 3 HP) w/ 1 HP left, & two shots landing on it at once
+	gameplay.enemies[0].take_damage(2);
+	let target = gameplay.enemies[0].collision_rectangle();
+	gameplay.quote = player::Player::new(&mut display, target.x, target.y);
+	gameplay.quote.start_fire();
+	gameplay.quote.start_fire();
+	gameplay.collide_projectiles(&mut display);
+
+	assert!(gameplay.enemies.is_empty());
+	assert_eq!(gameplay.quote.experience().progress().0, HIT_EXPERIENCE);
+	assert_eq!(gameplay.quote.projectiles_mut().iter()
+	           .filter(|&&(_, ref projectile)| projectile.is_alive()).count(), 1);
+}
+
+#[test]
+fn test_hits_credit_the_weapon_which_fired() {
+	let mut display = graphics::Graphics::headless();
+	let mut gameplay = Gameplay::new(&mut display);
+
+	let target = gameplay.enemies[0].collision_rectangle();
+	gameplay.quote = player::Player::new(&mut display, target.x, target.y);
+	gameplay.quote.start_fire();
+
+	// the shot lands after switching to the missile launcher
+	gameplay.quote.next_weapon();
+	gameplay.collide_projectiles(&mut display);
+	assert_eq!(gameplay.quote.experience().progress().0, 0);
+
+	gameplay.quote.previous_weapon();
+	assert_eq!(gameplay.quote.experience().progress().0, HIT_EXPERIENCE);
+}
+
+#[test]
+fn test_spikes_hurt_without_killing() {
+	let mut display = graphics::Graphics::headless();
+	let mut gameplay = Gameplay::new(&mut display);
+
+	let map_file = map::loader::parse("
+		backdrop  bk.bmp
+		tileset   tiles.bmp
+		size      5 4
+		start     2 1
+
+		[collision]
+		#...#
+		#...#
+		#^^^#
+		#####
+	").unwrap();
+	let spikes = map::Map::from_file(&mut display, &map_file);
+	assert!(gameplay.enter(&mut display, spikes).is_ok());
+
+	let (_, max_health) = gameplay.quote.health();
+	for tick in 0..60 {
+		gameplay.update(game::timestep(tick), &mut display);
+	}
+
+	// a single hit, then invincible for the rest of the second
+	let (health, _) = gameplay.quote.health();
+	assert_eq!(health, max_health - map::SPIKE_DAMAGE);
+	assert!(!gameplay.quote.is_dead());
+}
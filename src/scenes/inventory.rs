@@ -0,0 +1,76 @@
+use game;
+use graphics;
+use hud;
+use input::{self, Action};
+use scenes::{Scene, Transition};
+use sprite::{Drawable, Sprite};
+use units;
+use units::{AsGame,AsPixel};
+
+use sdl2::pixels::Color;
+use sdl2::rect::Rect;
+
+static FRAME_OFS_X: units::Game = units::Game(0.0);
+static FRAME_OFS_Y: units::Game = units::Game(0.0);
+static FRAME_W:     units::Game = units::Game(488.0);
+static FRAME_H:     units::Game = units::Game(16.0);
+
+// weapon icons are listed in a row just beneath the frame, w/ room
+// for the cursor to the left of each
+static ARMS_X: units::Tile        = units::Tile(2);
+static ARMS_Y: units::Tile        = units::Tile(3);
+static ARMS_SPACING: units::Tile  = units::Tile(2);
+
+/// Lists what the player is carrying, drawn over the paused world.
+pub struct Inventory {
+	frame: Sprite,
+	arms:  Vec<Sprite>,
+	cursor: Sprite,
+	current: usize, // position of the weapon being held
+}
+
+impl Inventory {
+	/// Lists the weapons found at `arms` on the arms sheets, w/ the one
+	/// at `current` highlighted.
+	pub fn new(display: &mut graphics::Graphics, arms: &[usize], current: usize) -> Inventory {
+		Inventory {
+			frame: Sprite::new(display, (FRAME_OFS_X, FRAME_OFS_Y), (FRAME_W, FRAME_H),
+			                   format!("assets/base/TextBox.bmp")),
+			arms:  arms.iter().map(|&index| hud::weapon_icon(display, index)).collect(),
+			cursor: hud::cursor(display),
+			current: current,
+		}
+	}
+}
+
+impl Scene for Inventory {
+	fn handle_input(&mut self, controller: &input::Input,
+	                _display: &mut graphics::Graphics) -> Transition {
+		if controller.was_pressed(Action::Inventory) {
+			Transition::Pop
+		} else {
+			Transition::None
+		}
+	}
+
+	fn draw(&mut self, display: &mut graphics::Graphics, _alpha: f64) {
+		let (units::Pixel(w), units::Pixel(h)) =
+			(game::SCREEN_WIDTH.to_pixel(), game::SCREEN_HEIGHT.to_pixel());
+		display.fill_rect(&Rect::new(0, 0, w as u32, h as u32), Color::RGBA(0, 0, 0, 160));
+
+		// the top edge of a text box, centered near the top of the screen
+		let x = (game::SCREEN_WIDTH.to_game() - FRAME_W) / units::Game(2.0);
+		self.frame.draw(display, (x, units::Tile(2).to_game()));
+
+		for (idx, icon) in self.arms.iter_mut().enumerate() {
+			let icon_x = x + ARMS_X.to_game() + (ARMS_SPACING * units::Tile(idx)).to_game();
+			icon.draw(display, (icon_x, ARMS_Y.to_game()));
+
+			if idx == self.current {
+				self.cursor.draw(display, (icon_x - units::Tile(1), ARMS_Y.to_game()));
+			}
+		}
+	}
+
+	fn is_overlay(&self) -> bool { true }
+}
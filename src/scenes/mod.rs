@@ -0,0 +1,64 @@
+use graphics;
+use input;
+use units;
+
+// Bring scenes into this crate's namespace
+pub use scenes::game_over::GameOver;
+pub use scenes::gameplay::Gameplay;
+pub use scenes::inventory::Inventory;
+pub use scenes::pause::Pause;
+pub use scenes::title::Title;
+
+// Load scene modules
+pub mod game_over;
+pub mod gameplay;
+pub mod inventory;
+pub mod pause;
+pub mod title;
+
+/// What a scene would like done to the scene stack.
+pub enum Transition {
+	/// Stay on this scene.
+	None,
+	/// Places a new scene on top of this one.
+	Push(Box<Scene>),
+	/// Removes this scene, revealing the one beneath.
+	Pop,
+	/// Swaps this scene out for another.
+	Replace(Box<Scene>),
+	/// Advances the scene beneath this one by a single tick.
+	Step,
+	/// Ends the game.
+	Quit,
+}
+
+/// One screen's worth of the game: a menu, an overlay, the world itself, etc.
+///
+/// Scenes are kept on a stack by `game::Game`. Only the topmost scene receives
+/// input and is updated, but scenes beneath an overlay are still drawn.
+pub trait Scene {
+	fn handle_input(&mut self, _controller: &input::Input,
+	                _display: &mut graphics::Graphics) -> Transition {
+		Transition::None
+	}
+
+	fn update(&mut self, _elapsed_time: units::Millis,
+	          _display: &mut graphics::Graphics) -> Transition {
+		Transition::None
+	}
+
+	/// `alpha` is how far (0.0 - 1.0) we are between the previous tick
+	/// and the next one.
+	fn draw(&mut self, display: &mut graphics::Graphics, alpha: f64);
+
+	/// Overlays are drawn on top of the scene beneath them,
+	/// rather than replacing it entirely.
+	fn is_overlay(&self) -> bool { false }
+
+	/// Called when this scene is on top of the stack once again,
+	/// after the scene above it was popped.
+	fn resume(&mut self, _display: &mut graphics::Graphics) {}
+
+	/// Returns the scene as the game's world, if it is one.
+	fn as_gameplay(&mut self) -> Option<&mut Gameplay> { None }
+}
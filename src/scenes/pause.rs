@@ -0,0 +1,42 @@
+use game;
+use graphics;
+use input::{self, Action};
+use scenes::{Scene, Transition};
+use units::AsPixel;
+use units;
+
+use sdl2::pixels::Color;
+use sdl2::rect::Rect;
+
+/// Freezes the scene beneath it, which is dimmed but remains visible.
+///
+/// While paused the frozen scene can be advanced one tick at a time,
+/// which is handy for inspecting jump arcs & enemy timing.
+pub struct Pause;
+
+impl Pause {
+	pub fn new() -> Pause {
+		Pause
+	}
+}
+
+impl Scene for Pause {
+	fn handle_input(&mut self, controller: &input::Input,
+	                _display: &mut graphics::Graphics) -> Transition {
+		if controller.was_pressed(Action::Pause) {
+			Transition::Pop
+		} else if controller.was_pressed(Action::Step) {
+			Transition::Step
+		} else {
+			Transition::None
+		}
+	}
+
+	fn draw(&mut self, display: &mut graphics::Graphics, _alpha: f64) {
+		let (units::Pixel(w), units::Pixel(h)) =
+			(game::SCREEN_WIDTH.to_pixel(), game::SCREEN_HEIGHT.to_pixel());
+		display.fill_rect(&Rect::new(0, 0, w as u32, h as u32), Color::RGBA(0, 0, 0, 96));
+	}
+
+	fn is_overlay(&self) -> bool { true }
+}
@@ -0,0 +1,41 @@
+use game;
+use graphics;
+use input::{self, Action};
+use scenes::{Scene, Transition};
+use sprite::{Drawable, Sprite};
+use units;
+use units::AsGame;
+
+static LOGO_W: units::Game = units::Game(288.0);
+static LOGO_H: units::Game = units::Game(80.0);
+
+/// Shows the logo until the player presses jump.
+pub struct Title {
+	logo: Sprite,
+}
+
+impl Title {
+	pub fn new(display: &mut graphics::Graphics) -> Title {
+		Title {
+			logo: Sprite::new(display, (units::Game(0.0), units::Game(0.0)), (LOGO_W, LOGO_H),
+			                  format!("assets/base/Title.bmp")),
+		}
+	}
+}
+
+impl Scene for Title {
+	fn handle_input(&mut self, controller: &input::Input,
+	                _display: &mut graphics::Graphics) -> Transition {
+		if controller.was_pressed(Action::Jump) {
+			Transition::Pop
+		} else {
+			Transition::None
+		}
+	}
+
+	fn draw(&mut self, display: &mut graphics::Graphics, _alpha: f64) {
+		let x = (game::SCREEN_WIDTH.to_game() - LOGO_W) / units::Game(2.0);
+		let y = (game::SCREEN_HEIGHT.to_game() - LOGO_H) / units::Game(3.0);
+		self.logo.draw(display, (x, y));
+	}
+}
@@ -84,6 +84,19 @@ impl Sprite {
 			size:          (norm_w,norm_h),
 		};
 	}
+
+	/// Crops (or extends) the sprite to `width`, measured from its left edge.
+	/// Useful for bars which fill up, e.g: the player's health.
+	pub fn set_width(&mut self, width: units::Game) {
+		let units::Pixel(wi) = width.to_pixel();
+		let (_, h) = self.size;
+
+		self.source_rect = rect::Rect::new(
+			self.source_rect.x(), self.source_rect.y(),
+			wi as u32, self.source_rect.height()
+		);
+		self.size = (width, h);
+	}
 }
 
 impl<C: AsGame> Drawable<C> for Sprite {
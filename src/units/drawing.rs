@@ -14,6 +14,15 @@ pub trait AsPixel { fn to_pixel(&self) -> Pixel; }
 #[derive(Clone, Copy, PartialEq,PartialOrd)]
 pub struct Game(pub f64);
 
+impl Game {
+	/// Moves `alpha` (0.0 - 1.0) of the way from `self` towards `to`.
+	#[inline]
+	pub fn lerp(self, to: Game, alpha: f64) -> Game {
+		let (Game(a), Game(b)) = (self, to);
+		Game(a + ((b - a) * alpha))
+	}
+}
+
 impl AsGame for Game {
 	#[inline]
 	fn to_game(&self) -> Game { *self }
@@ -85,6 +94,11 @@ impl AsPixel for Pixel {
 	fn to_pixel(&self) -> Pixel { *self }
 }
 
+impl AsGame for Pixel {
+	#[inline]
+	fn to_game(&self) -> Game { let Pixel(a) = *self; Game(a as f64 * SCALE) }
+}
+
 // Allow `+` operator for anything which can be converted `#to_pixel()`
 impl<T: AsPixel> Add<T> for Pixel {
 	type Output = Pixel;
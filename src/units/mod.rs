@@ -6,6 +6,7 @@ pub use units::physics::{Millis,Velocity,Acceleration};
 pub use units::physics::{Degrees,AngularVelocity};
 
 pub use units::physics::{Frame,Fps};
+pub use units::physics::HitPoints;
 
 // Load sub-libraries
 pub mod drawing;
@@ -156,5 +156,7 @@ impl Mul<Millis> for AngularVelocity {
 	}
 }
 
+pub type HitPoints = i32;
+
 pub type Frame = u64;
 pub type Fps = u64;
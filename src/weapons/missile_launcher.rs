@@ -0,0 +1,82 @@
+use graphics;
+use sprite::{Drawable, Facing, Looking, Sprite};
+use weapons::{Armament, Ballistics, Weapon};
+
+use units;
+
+static BULLET_SHEET: &'static str  = "assets/base/Bullet.bmp";
+
+// the missile launcher is the sixth gun on the arms sheets
+static ARMS_INDEX: usize = 5;
+
+static MAX_AMMO: i32 = 10;
+
+static BALLISTICS: Ballistics = Ballistics {
+	speed:     units::Velocity(0.35),
+	lifetimes: [units::Millis(400), units::Millis(500), units::Millis(600)],
+	damage:    [4, 6, 8],
+	max_shots: 1,
+};
+
+// missiles pointing west, up, east & down sit side by side on this row
+static MISSILE_ROW: units::Tile = units::Tile(1);
+
+/// A slow, heavy-hitting launcher: one missile may be in flight at once,
+/// and each one fired costs ammunition.
+pub struct MissileLauncher {
+	armament: Armament,
+	missiles: Vec<Sprite>, // west, up, east & down
+	ammo:     i32,
+}
+
+impl MissileLauncher {
+	pub fn new(graphics: &mut graphics::Graphics) -> MissileLauncher {
+		let armament = Armament::new(graphics, ARMS_INDEX, &BALLISTICS);
+
+		let missiles = (0..4).map(|column| {
+			Sprite::new(graphics, (units::Tile(column), MISSILE_ROW),
+			            (units::Tile(1), units::Tile(1)), BULLET_SHEET.to_string())
+		}).collect();
+
+		MissileLauncher {
+			armament: armament,
+			missiles: missiles,
+			ammo:     MAX_AMMO,
+		}
+	}
+}
+
+impl Weapon for MissileLauncher {
+	fn armament(&self) -> &Armament {
+		&self.armament
+	}
+
+	fn armament_mut(&mut self) -> &mut Armament {
+		&mut self.armament
+	}
+
+	fn start_fire(&mut self, x: units::Game, y: units::Game,
+	              facing: Facing, looking: Looking) {
+		if self.ammo > 0 && self.armament.fire(x, y, facing, looking) {
+			self.ammo -= 1;
+		}
+	}
+
+	fn draw_projectiles(&mut self, display: &mut graphics::Graphics, alpha: f64) {
+		for projectile in self.armament.projectiles() {
+			let (x, y) = projectile.center(alpha);
+			let column = match projectile.heading() {
+				(_, Looking::Up)                    => 1,
+				(_, Looking::Down)                  => 3,
+				(Facing::West, Looking::Horizontal) => 0,
+				(Facing::East, Looking::Horizontal) => 2,
+			};
+
+			self.missiles[column].draw(display, (x - units::HalfTile(1), y - units::HalfTile(1)));
+		}
+	}
+
+	fn ammo(&self) -> Option<(i32, i32)> {
+		Some((self.ammo, MAX_AMMO))
+	}
+}
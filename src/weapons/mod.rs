@@ -0,0 +1,443 @@
+use std::cmp;
+use std::collections::HashMap;
+
+use collisions::Rectangle;
+use graphics;
+use map;
+use sprite::{self, Drawable, Facing, Looking, Sprite};
+
+use units;
+use units::AsGame;
+
+// Bring weapons into this crate's namespace
+pub use weapons::missile_launcher::MissileLauncher;
+pub use weapons::polar_star::PolarStar;
+
+// Load weapon modules
+pub mod missile_launcher;
+pub mod polar_star;
+
+/// How wide a projectile is across the direction it travels in.
+static PROJECTILE_THICKNESS: units::Game  = units::Game(8.0);
+
+static ARMS_SHEET: &'static str  = "assets/base/Arms.bmp";
+
+// every gun on the arms sheet is the same size
+static GUN_W: units::HalfTile  = units::HalfTile(3);
+static GUN_H: units::HalfTile  = units::HalfTile(2);
+
+// nozzle positions, relative to the top-left of the gun
+static NOZZLE_HORIZ_Y: units::Game        = units::Game(23.0);
+static NOZZLE_HORIZ_WEST_X: units::Game   = units::Game(10.0);
+static NOZZLE_HORIZ_EAST_X: units::Game   = units::Game(38.0);
+static NOZZLE_UP_Y: units::Game           = units::Game(4.0);
+static NOZZLE_UP_WEST_X: units::Game      = units::Game(27.0);
+static NOZZLE_UP_EAST_X: units::Game      = units::Game(21.0);
+static NOZZLE_DOWN_Y: units::Game         = units::Game(28.0);
+static NOZZLE_DOWN_WEST_X: units::Game    = units::Game(29.0);
+static NOZZLE_DOWN_EAST_X: units::Game    = units::Game(19.0);
+
+// the gun is raised or lowered this far when aiming up or down
+static GUN_AIM_Y: units::Game = units::Game(8.0);
+
+/// Points needed to clear each of a weapon's levels.
+pub static EXPERIENCE: [i32; 3] = [10, 20, 10];
+
+/// Anything the player can shoot with.
+///
+/// A weapon owns the projectiles it has fired, they should be checked
+/// against the enemies each frame w/ `projectiles_mut()`.
+///
+/// Weapons grow stronger as they gain `Experience`.
+///
+/// The gun, projectiles & experience of a weapon are kept by its
+/// `Armament`, so a weapon need only draw its own projectiles.
+pub trait Weapon {
+	fn armament(&self) -> &Armament;
+	fn armament_mut(&mut self) -> &mut Armament;
+
+	/// Draws all projectiles in flight, interpolated by `alpha`.
+	fn draw_projectiles(&mut self, display: &mut graphics::Graphics, alpha: f64);
+
+	/// Fires a shot from the gun of a player standing at `x`, `y`,
+	/// the shot travels in the direction the player is aiming.
+	fn start_fire(&mut self, x: units::Game, y: units::Game,
+	              facing: Facing, looking: Looking) {
+		self.armament_mut().fire(x, y, facing, looking);
+	}
+
+	/// Advances all projectiles in flight, discarding those which have expired.
+	fn update(&mut self, elapsed_time: units::Millis, map: &map::Map) {
+		self.armament_mut().update(elapsed_time, map);
+	}
+
+	/// Draws the gun, held by a player drawn at `x`, `y`.
+	fn draw_gun(&mut self, display: &mut graphics::Graphics,
+	            x: units::Game, y: units::Game, facing: Facing, looking: Looking) {
+		self.armament_mut().gun.draw(display, x, y, facing, looking);
+	}
+
+	fn projectiles_mut(&mut self) -> &mut [Projectile] {
+		&mut self.armament_mut().projectiles[..]
+	}
+
+	fn experience(&self) -> &Experience {
+		&self.armament().experience
+	}
+
+	fn experience_mut(&mut self) -> &mut Experience {
+		&mut self.armament_mut().experience
+	}
+
+	/// The remaining & maximum ammunition, if the weapon ever runs out.
+	fn ammo(&self) -> Option<(i32, i32)> { None }
+
+	/// Where the weapon can be found on the arms sheets: `Arms.bmp` for
+	/// the gun as it is held, `ArmsImage.bmp` for its icon.
+	fn arms_index(&self) -> usize {
+		self.armament().arms_index
+	}
+}
+
+/// How the shots of a weapon behave, at each of its levels.
+pub struct Ballistics {
+	pub speed:      units::Velocity,
+	pub lifetimes:  [units::Millis; 3], // how far shots fly
+	pub damage:     [units::HitPoints; 3],
+	pub max_shots:  usize, // how many may be in flight at once
+}
+
+/// What every weapon has in common: the gun as it is held, the shots
+/// it has in flight, and the experience it has gathered.
+pub struct Armament {
+	gun:          Gun,
+	arms_index:   usize,
+	ballistics:   &'static Ballistics,
+	projectiles:  Vec<Projectile>,
+	experience:   Experience,
+}
+
+impl Armament {
+	/// The gun found at `arms_index` on the arms sheet, w/o any experience.
+	pub fn new(graphics: &mut graphics::Graphics, arms_index: usize,
+	           ballistics: &'static Ballistics) -> Armament {
+		Armament {
+			gun:         Gun::new(graphics, arms_index),
+			arms_index:  arms_index,
+			ballistics:  ballistics,
+			projectiles: Vec::new(),
+			experience:  Experience::new(&EXPERIENCE),
+		}
+	}
+
+	/// Fires a shot from the gun of a player standing at `x`, `y`, unless
+	/// too many are in flight already. Returns whether it was fired.
+	pub fn fire(&mut self, x: units::Game, y: units::Game,
+	            facing: Facing, looking: Looking) -> bool {
+		if self.projectiles.len() >= self.ballistics.max_shots { return false; }
+
+		let (nozzle_x, nozzle_y) = Gun::nozzle(x, y, facing, looking);
+		let level = self.experience.level();
+		self.projectiles.push(Projectile::new(
+			nozzle_x, nozzle_y, facing, looking, self.ballistics.speed,
+			self.ballistics.lifetimes[level - 1], self.ballistics.damage[level - 1], level,
+		));
+
+		true
+	}
+
+	fn update(&mut self, elapsed_time: units::Millis, map: &map::Map) {
+		for projectile in self.projectiles.iter_mut() {
+			projectile.update(elapsed_time, map);
+		}
+
+		self.projectiles.retain(|projectile| projectile.is_alive());
+	}
+
+	/// Discards every shot in flight.
+	pub fn clear_projectiles(&mut self) {
+		self.projectiles.clear();
+	}
+
+	/// The shots in flight which have yet to hit anything.
+	pub fn projectiles(&self) -> Vec<&Projectile> {
+		self.projectiles.iter().filter(|projectile| projectile.is_alive()).collect()
+	}
+}
+
+/// The gun held by the player, as drawn from the arms sheet.
+pub struct Gun {
+	sprites: HashMap<(Facing, Looking), Sprite>,
+}
+
+impl Gun {
+	/// Loads the gun found at `arms_index` on the arms sheet.
+	pub fn new(graphics: &mut graphics::Graphics, arms_index: usize) -> Gun {
+		let mut sprites = HashMap::new();
+		for &facing in sprite::FACINGS.iter() {
+			for &looking in sprite::LOOKINGS.iter() {
+				// each facing, then each direction, is another row on the sheet
+				let facing_row = match facing {
+					Facing::West => 0,
+					Facing::East => 1,
+				};
+
+				let looking_row = match looking {
+					Looking::Horizontal => 0,
+					Looking::Up         => 2,
+					Looking::Down       => 4,
+				};
+
+				let (units::HalfTile(w), units::HalfTile(h)) = (GUN_W, GUN_H);
+				sprites.insert((facing, looking), Sprite::new(graphics,
+					(units::HalfTile(w * arms_index as u64), units::HalfTile(h * (facing_row + looking_row))),
+					(GUN_W, GUN_H), ARMS_SHEET.to_string()
+				));
+			}
+		}
+
+		Gun { sprites: sprites }
+	}
+
+	/// Draws the gun, held by a player drawn at `x`, `y`.
+	pub fn draw(&mut self, display: &mut graphics::Graphics,
+	            x: units::Game, y: units::Game, facing: Facing, looking: Looking) {
+		let position = Gun::position(x, y, facing, looking);
+		self.sprites.get_mut(&(facing, looking)).unwrap().draw(display, position);
+	}
+
+	/// Where shots leave the gun, held by a player at `x`, `y`.
+	pub fn nozzle(x: units::Game, y: units::Game,
+	              facing: Facing, looking: Looking) -> (units::Game, units::Game) {
+		let (gun_x, gun_y) = Gun::position(x, y, facing, looking);
+		let (nozzle_x, nozzle_y) = match (facing, looking) {
+			(Facing::West, Looking::Horizontal) => (NOZZLE_HORIZ_WEST_X, NOZZLE_HORIZ_Y),
+			(Facing::East, Looking::Horizontal) => (NOZZLE_HORIZ_EAST_X, NOZZLE_HORIZ_Y),
+			(Facing::West, Looking::Up)         => (NOZZLE_UP_WEST_X, NOZZLE_UP_Y),
+			(Facing::East, Looking::Up)         => (NOZZLE_UP_EAST_X, NOZZLE_UP_Y),
+			(Facing::West, Looking::Down)       => (NOZZLE_DOWN_WEST_X, NOZZLE_DOWN_Y),
+			(Facing::East, Looking::Down)       => (NOZZLE_DOWN_EAST_X, NOZZLE_DOWN_Y),
+		};
+
+		(gun_x + nozzle_x, gun_y + nozzle_y)
+	}
+
+	/// The top-left of the gun, held by a player at `x`, `y`.
+	fn position(x: units::Game, y: units::Game,
+	            facing: Facing, looking: Looking) -> (units::Game, units::Game) {
+		let gun_x = match facing {
+			Facing::West => x - units::HalfTile(1),
+			Facing::East => x,
+		};
+
+		let gun_y = match looking {
+			Looking::Up         => y - GUN_AIM_Y,
+			Looking::Down       => y + GUN_AIM_Y,
+			Looking::Horizontal => y,
+		};
+
+		(gun_x, gun_y)
+	}
+}
+
+/// The experience gathered by a weapon, which determines its level.
+///
+/// Each level takes a number of points to clear, once the last level
+/// has been cleared the weapon is at its maximum.
+/// Experience can be lost, and w/ it levels.
+pub struct Experience {
+	points:      i32,
+	thresholds:  &'static [i32],
+}
+
+impl Experience {
+	/// No experience, where `thresholds` are the points needed to clear
+	/// each level in turn.
+	pub fn new(thresholds: &'static [i32]) -> Experience {
+		Experience { points: 0, thresholds: thresholds }
+	}
+
+	/// The current level, starting from 1.
+	pub fn level(&self) -> usize {
+		let mut total = 0;
+		for (idx, threshold) in self.thresholds.iter().enumerate() {
+			total += *threshold;
+			if self.points < total { return idx + 1; }
+		}
+
+		self.thresholds.len()
+	}
+
+	pub fn is_max(&self) -> bool {
+		self.points >= self.total()
+	}
+
+	/// The points earned towards the current level, and the points needed to clear it.
+	pub fn progress(&self) -> (i32, i32) {
+		let level = self.level();
+		let cleared: i32 = self.thresholds[..(level - 1)].iter().fold(0, |sum, points| sum + *points);
+		let needed = self.thresholds[level - 1];
+
+		(cmp::min(self.points - cleared, needed), needed)
+	}
+
+	pub fn gain(&mut self, points: i32) {
+		self.points = cmp::min(self.points + points, self.total());
+	}
+
+	pub fn lose(&mut self, points: i32) {
+		self.points = cmp::max(self.points - points, 0);
+	}
+
+	fn total(&self) -> i32 {
+		self.thresholds.iter().fold(0, |sum, points| sum + *points)
+	}
+}
+
+/// A shot which flies in a straight line until it hits a wall,
+/// hits an enemy, or its lifetime runs out.
+pub struct Projectile {
+	x: units::Game,
+	y: units::Game,
+	prev_x: units::Game,
+	prev_y: units::Game,
+
+	velocity_x: units::Velocity,
+	velocity_y: units::Velocity,
+	heading:    (Facing, Looking),
+
+	age:       units::Millis,
+	lifetime:  units::Millis,
+	damage:    units::HitPoints,
+	level:     usize,
+	is_alive:  bool,
+}
+
+impl Projectile {
+	/// A projectile centered on `x`, `y` travelling in the direction given
+	/// by `facing` & `looking`. (Looking up or down takes precedence.)
+	///
+	/// `level` is the level of the weapon which fired it.
+	pub fn new(x: units::Game, y: units::Game,
+	           facing: Facing, looking: Looking,
+	           speed: units::Velocity, lifetime: units::Millis,
+	           damage: units::HitPoints, level: usize) -> Projectile {
+		let still = units::Velocity(0.0);
+		let (velocity_x, velocity_y) = match (facing, looking) {
+			(_, Looking::Up)                => (still, -speed),
+			(_, Looking::Down)              => (still, speed),
+			(Facing::West, Looking::Horizontal) => (-speed, still),
+			(Facing::East, Looking::Horizontal) => (speed, still),
+		};
+
+		Projectile {
+			x: x, y: y,
+			prev_x: x, prev_y: y,
+
+			velocity_x: velocity_x,
+			velocity_y: velocity_y,
+			heading:    (facing, looking),
+
+			age:       units::Millis(0),
+			lifetime:  lifetime,
+			damage:    damage,
+			level:     level,
+			is_alive:  true,
+		}
+	}
+
+	/// Moves the projectile, returns `false` once it has hit a wall or expired.
+	pub fn update(&mut self, elapsed_time: units::Millis, map: &map::Map) -> bool {
+		self.prev_x = self.x;
+		self.prev_y = self.y;
+		self.x = self.x + (self.velocity_x * elapsed_time);
+		self.y = self.y + (self.velocity_y * elapsed_time);
+
+		self.age = self.age + elapsed_time;
+		if self.age >= self.lifetime { self.is_alive = false; }
+
+		// projectiles leaving the map are lost, those hitting a wall are stopped
+		let rectangle = self.collision_rectangle();
+		if rectangle.left() < units::Game(0.0) || rectangle.right() >= map.width()
+		|| rectangle.top() < units::Game(0.0) || rectangle.bottom() >= map.height() {
+			self.is_alive = false;
+		} else if map.get_colliding_tiles(&rectangle).iter()
+		             .any(|tile| tile.tile_type == map::TileType::Wall) {
+			self.is_alive = false;
+		}
+
+		self.is_alive
+	}
+
+	/// Should be called when the projectile strikes an enemy: it is used up.
+	pub fn collide_with_enemy(&mut self) {
+		self.is_alive = false;
+	}
+
+	pub fn is_alive(&self) -> bool {
+		self.is_alive
+	}
+
+	pub fn is_vertical(&self) -> bool {
+		self.velocity_x == units::Velocity(0.0)
+	}
+
+	/// How much damage the projectile deals to an enemy it strikes.
+	pub fn damage(&self) -> units::HitPoints {
+		self.damage
+	}
+
+	pub fn level(&self) -> usize {
+		self.level
+	}
+
+	/// The direction the projectile was fired in.
+	pub fn heading(&self) -> (Facing, Looking) {
+		self.heading
+	}
+
+	/// The center of the projectile, `alpha` of the way between its position
+	/// on the previous tick & its current position.
+	pub fn center(&self, alpha: f64) -> (units::Game, units::Game) {
+		(self.prev_x.lerp(self.x, alpha), self.prev_y.lerp(self.y, alpha))
+	}
+
+	/// A thin box, one tile long, along the projectile's direction of travel.
+	pub fn collision_rectangle(&self) -> Rectangle {
+		let (length, thickness) = (units::Tile(1).to_game(), PROJECTILE_THICKNESS);
+		let (width, height) = if self.is_vertical() {
+			(thickness, length)
+		} else {
+			(length, thickness)
+		};
+
+		Rectangle {
+			x: self.x - (width / units::Game(2.0)),
+			y: self.y - (height / units::Game(2.0)),
+			width: width, height: height,
+		}
+	}
+}
+
+#[test]
+fn test_experience_levels() {
+	static THRESHOLDS: [i32; 3] = [10, 20, 10];
+	let mut experience = Experience::new(&THRESHOLDS);
+	assert_eq!(experience.level(), 1);
+
+	experience.gain(15);
+	assert_eq!(experience.level(), 2);
+	assert_eq!(experience.progress(), (5, 20));
+
+	// experience is capped once the last level has been cleared
+	experience.gain(100);
+	assert_eq!(experience.level(), 3);
+	assert!(experience.is_max());
+	assert_eq!(experience.progress(), (10, 10));
+
+	// losing experience may cost a level
+	experience.lose(25);
+	assert_eq!(experience.level(), 2);
+	experience.lose(100);
+	assert_eq!(experience.progress(), (0, 10));
+}
@@ -0,0 +1,118 @@
+use graphics;
+use sprite::{Drawable, Sprite};
+use weapons::{Armament, Ballistics, Weapon};
+
+use units;
+
+static BULLET_SHEET: &'static str  = "assets/base/Bullet.bmp";
+
+// the polar star is the third gun on the arms sheets
+static ARMS_INDEX: usize = 2;
+
+static BALLISTICS: Ballistics = Ballistics {
+	speed:     units::Velocity(0.6),
+	lifetimes: [units::Millis(120), units::Millis(160), units::Millis(200)],
+	damage:    [1, 2, 4],
+	max_shots: 2,
+};
+
+// per level: the horizontal & vertical projectiles' columns, and their row
+static PROJECTILE_SPRITES: [(units::Tile, units::Tile, units::Tile); 3] = [
+	(units::Tile(8),  units::Tile(9),  units::Tile(2)),
+	(units::Tile(10), units::Tile(11), units::Tile(2)),
+	(units::Tile(8),  units::Tile(9),  units::Tile(3)),
+];
+
+/// The player's first gun: it fires short-lived bolts, no more than two
+/// of which may be in flight at once.
+///
+/// Bolts fly further & hit harder as the gun levels up.
+pub struct PolarStar {
+	armament: Armament,
+	projectile_sprites: Vec<(Sprite, Sprite)>, // horizontal & vertical, per level
+}
+
+impl PolarStar {
+	pub fn new(graphics: &mut graphics::Graphics) -> PolarStar {
+		let armament = Armament::new(graphics, ARMS_INDEX, &BALLISTICS);
+
+		let projectile_sprites = PROJECTILE_SPRITES.iter().map(|&(horizontal_x, vertical_x, y)| {
+			let mut projectile = |x: units::Tile| {
+				Sprite::new(graphics, (x, y),
+				            (units::Tile(1), units::Tile(1)), BULLET_SHEET.to_string())
+			};
+
+			(projectile(horizontal_x), projectile(vertical_x))
+		}).collect();
+
+		PolarStar {
+			armament: armament,
+			projectile_sprites: projectile_sprites,
+		}
+	}
+
+	pub fn projectile_count(&self) -> usize {
+		self.armament.projectiles().len()
+	}
+}
+
+impl Weapon for PolarStar {
+	fn armament(&self) -> &Armament {
+		&self.armament
+	}
+
+	fn armament_mut(&mut self) -> &mut Armament {
+		&mut self.armament
+	}
+
+	fn draw_projectiles(&mut self, display: &mut graphics::Graphics, alpha: f64) {
+		for projectile in self.armament.projectiles() {
+			let (x, y) = projectile.center(alpha);
+			let (ref mut horizontal, ref mut vertical) = self.projectile_sprites[projectile.level() - 1];
+			let sprite = if projectile.is_vertical() { vertical } else { horizontal };
+
+			sprite.draw(display, (x - units::HalfTile(1), y - units::HalfTile(1)));
+		}
+	}
+}
+
+#[test]
+fn test_projectiles_hit_walls_or_expire() {
+	use map;
+	use sprite::{Facing, Looking};
+	use units::AsGame;
+
+	let map_file = map::loader::parse("
+		backdrop assets/base/bkBlue.bmp
+		tileset  assets/base/Stage/PrtCave.bmp
+		size     10 5
+
+		[collision]
+		##########
+		#........#
+		#........#
+		#........#
+		##########
+	").unwrap();
+
+	let mut display = graphics::Graphics::headless();
+	let map = map::Map::from_file(&mut display, &map_file);
+	let mut gun = PolarStar::new(&mut display);
+	let (x, y) = (units::Tile(1).to_game(), units::Tile(2).to_game());
+
+	// fired point blank into the wall behind the player
+	gun.start_fire(x, y, Facing::West, Looking::Horizontal);
+	gun.update(units::Millis(16), &map);
+	assert_eq!(gun.projectile_count(), 0);
+
+	// only two shots may be in flight at once
+	for _ in 0..3 {
+		gun.start_fire(x, y, Facing::East, Looking::Horizontal);
+	}
+	assert_eq!(gun.projectile_count(), 2);
+
+	gun.update(units::Millis(100), &map);
+	assert_eq!(gun.projectile_count(), 2);
+	gun.update(BALLISTICS.lifetimes[0], &map);
+	assert_eq!(gun.projectile_count(), 0);
+}